@@ -37,4 +37,64 @@ pub enum Error {
     InvalidCloseTime,
     #[msg("Event has been closed")]
     EventClosed,
+
+    /// 15
+    #[msg("Zero amount")]
+    ZeroAmount,
+    #[msg("Invalid strike price")]
+    InvalidStrikePrice,
+    #[msg("Invalid price decimals")]
+    InvalidPriceDecimals,
+    #[msg("Invalid price")]
+    InvalidPrice,
+    #[msg("Event is not resolved by an oracle")]
+    NotOracleEvent,
+
+    /// 20
+    #[msg("Event has not been closed")]
+    EventNotClosed,
+    #[msg("The oracle resolve period has expired")]
+    OracleResolvePeriodExpired,
+    #[msg("Event must be resolved by the oracle")]
+    OracleResolvePeriodActive,
+    #[msg("Event outcome has not been finalized")]
+    EventNotFinalized,
+    #[msg("The dispute period has expired")]
+    DisputePeriodExpired,
+
+    /// 25
+    #[msg("Invalid arbiter")]
+    InvalidArbiter,
+    #[msg("Event is not disputed")]
+    EventNotDisputed,
+    #[msg("The order has not expired")]
+    OrderNotExpired,
+    #[msg("Invalid number of outcomes")]
+    InvalidOutcomeCount,
+    #[msg("Currency mint is not allowed for this event")]
+    CurrencyMintNotAllowed,
+
+    /// 30
+    #[msg("Currency mint does not match the order")]
+    InvalidCurrencyMint,
+    #[msg("Currency account does not belong to the order")]
+    InvalidCurrencyAccount,
+    #[msg("Invalid number of currency mints")]
+    InvalidCurrencyMints,
+    #[msg("Order does not belong to this event")]
+    InvalidOrder,
+    #[msg("No orders could be filled")]
+    NoOrdersFilled,
+
+    /// 35
+    #[msg("Invalid fill account")]
+    InvalidFillAccount,
+    #[msg("Price feed is not owned by pyth")]
+    InvalidPriceFeed,
+    #[msg("The arbiter deadline has passed")]
+    ArbiterPeriodExpired,
+    #[msg("The arbiter deadline has not passed")]
+    ArbiterPeriodNotExpired,
+    #[msg("Invalid order authority")]
+    InvalidOrderAuthority,
 }
\ No newline at end of file
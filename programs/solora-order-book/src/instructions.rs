@@ -4,6 +4,12 @@ mod fill_order;
 mod cancel_order;
 mod settle_event;
 mod settle_fill;
+mod resolve_with_oracle;
+mod dispute_event;
+mod resolve_dispute;
+mod expire_dispute;
+mod reclaim_expired;
+mod market_fill;
 
 pub use create_event::*;
 pub use create_order::*;
@@ -11,6 +17,12 @@ pub use fill_order::*;
 pub use cancel_order::*;
 pub use settle_event::*;
 pub use settle_fill::*;
+pub use resolve_with_oracle::*;
+pub use dispute_event::*;
+pub use resolve_dispute::*;
+pub use expire_dispute::*;
+pub use reclaim_expired::*;
+pub use market_fill::*;
 
 /*
 mod swap;
@@ -1,9 +1,9 @@
 use anchor_lang::prelude::*;
 use anchor_spl::token;
-use anchor_spl::token::{CloseAccount};
+use anchor_spl::token::{CloseAccount, TokenAccount};
 use crate::state::{Event, Order};
 use crate::error::Error;
-use crate::util::{is_native_mint, transfer};
+use crate::util::{assert_order_currency, is_native_mint, transfer, transfer_sol_pda};
 
 #[derive(Accounts)]
 #[instruction(index: u32, amount: u64)]
@@ -33,24 +33,69 @@ pub struct CancelOrder<'info> {
 pub fn cancel_order<'info>(
     ctx: Context<'_, '_, '_, 'info, CancelOrder<'info>>,
     index: u32,
-    _amount: u64
+    amount: u64
 ) -> Result<()> {
+    if amount == 0 {
+        return err!(Error::ZeroAmount);
+    }
+
     let is_native = is_native_mint(ctx.accounts.order.currency_mint);
+    let order = &mut ctx.accounts.order;
+    order.remaining_ask = order.remaining_ask.checked_sub(amount)
+        .ok_or(Error::OverflowError)?;
+
+    // Nothing left to fill so we can return funds and close order account(s). Fills hold their
+    // own funds so they don't need the order to settle.
+    let close_order = order.remaining_ask == 0;
+
+    // Otherwise only return the part of the obligation that backed the cancelled ask
+    let refund_amount = if close_order {
+        order.amount
+    } else {
+        (amount as u128)
+            .checked_mul(10_000).unwrap()
+            .checked_div(order.ask_bps as u128).unwrap() as u64
+    };
+    order.amount = order.amount.checked_sub(refund_amount)
+        .ok_or(Error::OverflowError)?;
+
+    if is_native {
+        if !close_order && refund_amount > 0 {
+            transfer_sol_pda(
+                &mut ctx.accounts.order.to_account_info(),
+                &mut ctx.accounts.authority.to_account_info(),
+                refund_amount
+            )?;
+        }
+    } else {
+        let remaining_accounts = &mut ctx.remaining_accounts.iter();
+        let currency_mint = next_account_info(remaining_accounts)?;
+        let order_currency_account = next_account_info(remaining_accounts)?;
+        let user_currency_account = next_account_info(remaining_accounts)?;
+        let token_program = next_account_info(remaining_accounts)?;
+        let ata_program = next_account_info(remaining_accounts)?;
+        let rent = next_account_info(remaining_accounts)?;
 
-    // No fills so we can return funds and close order account(s)
-    if ctx.accounts.order.fills.len() == 0 {
-        if !is_native {
-            let remaining_accounts = &mut ctx.remaining_accounts.iter();
-            let currency_mint = next_account_info(remaining_accounts)?;
-            let order_currency_account = next_account_info(remaining_accounts)?;
-            let user_currency_account = next_account_info(remaining_accounts)?;
-            let token_program = next_account_info(remaining_accounts)?;
-            let ata_program = next_account_info(remaining_accounts)?;
-            let rent = next_account_info(remaining_accounts)?;
-            let index_bytes = &index.to_le_bytes();
-            let seeds = ctx.accounts.order.auth_seeds(index_bytes);
-            let auth_seeds = seeds.as_ref();
+        assert_order_currency(
+            &ctx.accounts.event,
+            &ctx.accounts.order.key(),
+            &ctx.accounts.order.currency_mint,
+            currency_mint,
+            order_currency_account,
+        )?;
 
+        let index_bytes = &index.to_le_bytes();
+        let seeds = ctx.accounts.order.auth_seeds(index_bytes);
+        let auth_seeds = seeds.as_ref();
+
+        // Rounding can leave dust behind which belongs to the maker
+        let refund_amount = if close_order {
+            Account::<'info, TokenAccount>::try_from(order_currency_account)?.amount
+        } else {
+            refund_amount
+        };
+
+        if refund_amount > 0 {
             transfer(
                 &ctx.accounts.order.to_account_info(),
                 &ctx.accounts.authority.to_account_info(),
@@ -64,9 +109,11 @@ pub fn cancel_order<'info>(
                 rent.into(),
                 auth_seeds.into(),
                 None,
-                ctx.accounts.order.amount,
+                refund_amount,
             )?;
+        }
 
+        if close_order {
             token::close_account(
                 CpiContext::new(
                     token_program.to_account_info(),
@@ -78,11 +125,34 @@ pub fn cancel_order<'info>(
                 ).with_signer(&[auth_seeds]),
             )?;
         }
+    }
 
+    emit!(OrderCancelled {
+        event: ctx.accounts.event.key(),
+        order: ctx.accounts.order.key(),
+        authority: ctx.accounts.authority.key(),
+        amount,
+        refund_amount,
+        remaining_ask: ctx.accounts.order.remaining_ask,
+        closed: close_order,
+    });
+
+    if close_order {
         ctx.accounts.order.close(ctx.accounts.authority.to_account_info())?;
-    } else {
-        // TODO: Reduce bet amount if partially filled
     }
 
     Ok(())
+}
+
+#[event]
+pub struct OrderCancelled {
+    pub event: Pubkey,
+    pub order: Pubkey,
+    pub authority: Pubkey,
+    /// Amount removed from the remaining ask
+    pub amount: u64,
+    /// Amount of the order returned to the authority
+    pub refund_amount: u64,
+    pub remaining_ask: u64,
+    pub closed: bool,
 }
\ No newline at end of file
@@ -1,5 +1,15 @@
 use anchor_lang::prelude::*;
-use crate::state::{Event, EVENT_SIZE};
+use pyth_sdk_solana::load_price_feed_from_account_info;
+use crate::state::{
+    Comparison,
+    CreateEventParams,
+    Event,
+    EVENT_SIZE,
+    EVENT_VERSION,
+    MAX_CURRENCY_MINTS,
+    OUTCOME_VOID,
+    PYTH_PROGRAM_IDS,
+};
 use crate::error::Error;
 
 #[derive(Accounts)]
@@ -28,11 +38,37 @@ pub struct CreateEvent<'info> {
 pub fn create_event<'info>(
     ctx: Context<'_, '_, '_, 'info, CreateEvent<'info>>,
     id: [u8; 32],
-    fee_account: Pubkey,
-    fee_bps: u32,
-    close_time: i64,
-    metadata_uri: String
+    params: CreateEventParams,
 ) -> Result<()> {
+    let CreateEventParams {
+        fee_account,
+        fee_bps,
+        close_time,
+        metadata_uri,
+        comparison,
+        strike_price,
+        strike_price_high,
+        price_decimals,
+        arbiter,
+        dispute_period,
+        dispute_bond,
+        num_outcomes,
+        outcome_labels_hash,
+        currency_mints,
+    } = params;
+
+    if currency_mints.is_empty() || currency_mints.len() > MAX_CURRENCY_MINTS {
+        return err!(Error::InvalidCurrencyMints);
+    }
+
+    if num_outcomes < 2 || num_outcomes == OUTCOME_VOID {
+        return err!(Error::InvalidOutcomeCount);
+    }
+
+    if dispute_period > 0 && arbiter == Pubkey::default() {
+        return err!(Error::InvalidArbiter);
+    }
+
     if close_time != 0 {
         let timestamp = Clock::get()?.unix_timestamp;
         if close_time <= timestamp {
@@ -40,14 +76,100 @@ pub fn create_event<'info>(
         }
     }
 
+    // Oracle events are resolved from the pyth feed passed in remaining_accounts at close time
+    let pyth_feed = if comparison != Comparison::None {
+        if close_time == 0 {
+            return err!(Error::InvalidCloseTime);
+        }
+
+        // The oracle resolves to either outcome 1 or 2
+        if num_outcomes != 2 {
+            return err!(Error::InvalidOutcomeCount);
+        }
+
+        if comparison == Comparison::Between && strike_price > strike_price_high {
+            return err!(Error::InvalidStrikePrice);
+        }
+
+        let remaining_accounts = &mut ctx.remaining_accounts.iter();
+        let pyth_feed = next_account_info(remaining_accounts)?;
+        if !PYTH_PROGRAM_IDS.contains(pyth_feed.owner) {
+            return err!(Error::InvalidPriceFeed);
+        }
+
+        let price_feed = load_price_feed_from_account_info(pyth_feed)
+            .map_err(|_| Error::InvalidPrice)?;
+        let expo = price_feed.get_price_unchecked().expo;
+        if price_decimals as i64 > -(expo as i64) {
+            return err!(Error::InvalidPriceDecimals);
+        }
+
+        pyth_feed.key()
+    } else {
+        Pubkey::default()
+    };
+
     let event = &mut ctx.accounts.event;
     event.bump = [*ctx.bumps.get("event").unwrap()];
+    event.version = EVENT_VERSION;
     event.authority = ctx.accounts.authority.key();
     event.id = id;
     event.fee_account = fee_account;
     event.fee_bps = fee_bps;
     event.close_time = close_time;
+    event.pyth_feed = pyth_feed;
+    event.comparison = comparison;
+    event.strike_price = strike_price;
+    event.strike_price_high = strike_price_high;
+    event.price_decimals = price_decimals;
+    event.arbiter = arbiter;
+    event.dispute_period = dispute_period;
+    event.dispute_bond = dispute_bond;
+    event.num_outcomes = num_outcomes;
+    event.outcome_labels_hash = outcome_labels_hash;
+    event.currency_mints = currency_mints;
     event.metadata_uri = metadata_uri;
 
+    emit!(EventCreated {
+        event: event.key(),
+        authority: event.authority,
+        id,
+        fee_account,
+        fee_bps,
+        close_time,
+        pyth_feed,
+        comparison,
+        strike_price,
+        strike_price_high,
+        price_decimals,
+        arbiter,
+        dispute_period,
+        dispute_bond,
+        num_outcomes,
+        outcome_labels_hash,
+        currency_mints: event.currency_mints.clone(),
+    });
+
     Ok(())
+}
+
+#[event]
+pub struct EventCreated {
+    pub event: Pubkey,
+    pub authority: Pubkey,
+    pub id: [u8; 32],
+    pub fee_account: Pubkey,
+    pub fee_bps: u32,
+    pub close_time: i64,
+    pub pyth_feed: Pubkey,
+    pub comparison: Comparison,
+    pub strike_price: u64,
+    pub strike_price_high: u64,
+    pub price_decimals: u8,
+    pub arbiter: Pubkey,
+    pub dispute_period: u32,
+    pub dispute_bond: u64,
+    pub num_outcomes: u8,
+    pub outcome_labels_hash: [u8; 32],
+    pub currency_mints: Vec<Pubkey>,
 }
\ No newline at end of file
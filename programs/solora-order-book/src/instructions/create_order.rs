@@ -1,7 +1,7 @@
 use anchor_lang::prelude::*;
 use crate::state::{Event, Order, ORDER_SIZE};
 use crate::error::Error;
-use crate::util::{transfer, transfer_sol};
+use crate::util::{assert_order_currency, transfer, transfer_sol};
 
 #[derive(Accounts)]
 pub struct CreateOrder<'info> {
@@ -47,7 +47,7 @@ pub fn create_order<'info>(
         return err!(Error::EventClosed);
     }
 
-    if outcome == 0 {
+    if !ctx.accounts.event.is_valid_outcome(outcome) {
         return err!(Error::InvalidOutcome);
     }
 
@@ -68,10 +68,14 @@ pub fn create_order<'info>(
     order.ask_bps = ask_bps;
     order.remaining_ask = total_ask;
     order.expiry = expiry;
-    order.fills = Vec::new();
+    order.fill_index = 0;
 
     // If there are remaining_accounts populated we're using an alt currency mint
     if ctx.remaining_accounts.len() == 0 {
+        if !ctx.accounts.event.is_allowed_mint(&spl_token::native_mint::ID) {
+            return err!(Error::CurrencyMintNotAllowed);
+        }
+
         transfer_sol(
             &ctx.accounts.authority.to_account_info(),
             &order.to_account_info(),
@@ -90,6 +94,14 @@ pub fn create_order<'info>(
         let ata_program = next_account_info(remaining_accounts)?;
         let rent = next_account_info(remaining_accounts)?;
 
+        assert_order_currency(
+            &ctx.accounts.event,
+            &order.key(),
+            currency_mint.key,
+            currency_mint,
+            order_currency_account,
+        )?;
+
         transfer(
             &ctx.accounts.authority.to_account_info(),
             &order.to_account_info(),
@@ -109,8 +121,36 @@ pub fn create_order<'info>(
         order.currency_mint = currency_mint.key();
     }
 
+    emit!(OrderCreated {
+        event: ctx.accounts.event.key(),
+        order: order.key(),
+        authority: order.authority,
+        index: order.index,
+        outcome,
+        amount: order.amount,
+        currency_mint: order.currency_mint,
+        ask_bps,
+        ask: order.remaining_ask,
+        expiry,
+    });
+
     ctx.accounts.event.order_index = ctx.accounts.event.order_index.checked_add(1)
         .ok_or(Error::OverflowError)?;
 
     Ok(())
+}
+
+#[event]
+pub struct OrderCreated {
+    pub event: Pubkey,
+    pub order: Pubkey,
+    pub authority: Pubkey,
+    pub index: u32,
+    pub outcome: u8,
+    pub amount: u64,
+    pub currency_mint: Pubkey,
+    pub ask_bps: u32,
+    /// Total amount fillers can pay to take the order
+    pub ask: u64,
+    pub expiry: i64,
 }
\ No newline at end of file
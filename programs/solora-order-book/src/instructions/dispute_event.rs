@@ -0,0 +1,86 @@
+use anchor_lang::prelude::*;
+use crate::state::{Dispute, DISPUTE_SIZE, Event};
+use crate::error::Error;
+use crate::util::transfer_sol;
+
+#[derive(Accounts)]
+#[instruction(outcome: u8)]
+pub struct DisputeEvent<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    #[account(
+    mut,
+    seeds = [b"event".as_ref(), event.id.as_ref()],
+    bump = event.bump[0],
+    constraint = event.outcome != 0 @ Error::EventNotSettled,
+    constraint = event.is_valid_result(outcome) && outcome != event.outcome @ Error::InvalidOutcome,
+    )]
+    pub event: Box<Account<'info, Event>>,
+
+    #[account(
+    init,
+    seeds = [b"dispute".as_ref(), event.key().as_ref()],
+    bump,
+    space = DISPUTE_SIZE,
+    payer = authority,
+    )]
+    pub dispute: Box<Account<'info, Dispute>>,
+
+    pub system_program: Program<'info, System>,
+    pub rent: Sysvar<'info, Rent>,
+}
+
+/// Challenges the settled outcome by posting the event's dispute bond. Fills can't be settled
+/// until the arbiter rules on the dispute or its deadline passes. There is only one dispute per
+/// event, so the first disputer holds it for everyone.
+pub fn dispute_event<'info>(
+    ctx: Context<'_, '_, '_, 'info, DisputeEvent<'info>>,
+    outcome: u8,
+) -> Result<()> {
+    let event = &mut ctx.accounts.event;
+
+    let timestamp = Clock::get()?.unix_timestamp;
+    if event.finalized || timestamp >= event.settle_time + event.dispute_period as i64 {
+        return err!(Error::DisputePeriodExpired);
+    }
+
+    event.is_disputed = true;
+
+    let dispute = &mut ctx.accounts.dispute;
+    dispute.bump = [*ctx.bumps.get("dispute").unwrap()];
+    dispute.event = event.key();
+    dispute.authority = ctx.accounts.authority.key();
+    dispute.outcome = outcome;
+    dispute.bond = event.dispute_bond;
+
+    if dispute.bond > 0 {
+        transfer_sol(
+            &ctx.accounts.authority.to_account_info(),
+            &dispute.to_account_info(),
+            &ctx.accounts.system_program.to_account_info(),
+            None,
+            dispute.bond,
+        )?;
+    }
+
+    emit!(EventDisputed {
+        event: event.key(),
+        dispute: dispute.key(),
+        authority: dispute.authority,
+        outcome,
+        bond: dispute.bond,
+    });
+
+    Ok(())
+}
+
+#[event]
+pub struct EventDisputed {
+    pub event: Pubkey,
+    pub dispute: Pubkey,
+    pub authority: Pubkey,
+    /// Outcome the disputer claims is correct
+    pub outcome: u8,
+    pub bond: u64,
+}
@@ -0,0 +1,54 @@
+use anchor_lang::prelude::*;
+use crate::state::{Dispute, Event};
+use crate::error::Error;
+use crate::instructions::EventResolved;
+
+#[derive(Accounts)]
+pub struct ExpireDispute<'info> {
+    /// CHECK: Safe due to dispute constraint
+    #[account(mut)]
+    pub authority: UncheckedAccount<'info>,
+
+    #[account(
+    mut,
+    seeds = [b"event".as_ref(), event.id.as_ref()],
+    bump = event.bump[0],
+    constraint = event.is_disputed @ Error::EventNotDisputed,
+    )]
+    pub event: Box<Account<'info, Event>>,
+
+    #[account(
+    mut,
+    close = authority,
+    seeds = [b"dispute".as_ref(), event.key().as_ref()],
+    bump = dispute.bump[0],
+    has_one = event,
+    has_one = authority,
+    )]
+    pub dispute: Box<Account<'info, Dispute>>,
+}
+
+/// Finalizes a disputed event the arbiter didn't rule on in time. The settled outcome stands and
+/// the bond is returned to the disputer, since it was never shown to be wrong. Anyone can call
+/// this, fills can already be settled once the arbiter deadline has passed.
+pub fn expire_dispute<'info>(ctx: Context<'_, '_, '_, 'info, ExpireDispute<'info>>) -> Result<()> {
+    let event = &mut ctx.accounts.event;
+
+    let timestamp = Clock::get()?.unix_timestamp;
+    if timestamp < event.arbiter_deadline() {
+        return err!(Error::ArbiterPeriodNotExpired);
+    }
+
+    event.is_disputed = false;
+    event.finalized = true;
+
+    emit!(EventResolved {
+        event: event.key(),
+        resolver: event.authority,
+        outcome: event.outcome,
+        settle_time: event.settle_time,
+        finalized: true,
+    });
+
+    Ok(())
+}
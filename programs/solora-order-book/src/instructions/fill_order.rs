@@ -1,7 +1,14 @@
 use anchor_lang::prelude::*;
-use crate::state::{Event, Fill, Order};
+use crate::state::{Event, Fill, FILL_SIZE, Order};
 use crate::error::Error;
-use crate::util::{is_native_mint, transfer, transfer_sol};
+use crate::util::{
+    assert_order_currency,
+    create_pda_account,
+    is_native_mint,
+    transfer,
+    transfer_sol,
+    transfer_sol_pda,
+};
 
 #[derive(Accounts)]
 #[instruction(index: u32, outcome: u8)]
@@ -13,17 +20,18 @@ pub struct FillOrder<'info> {
     mut,
     seeds = [b"order".as_ref(), event.key().as_ref(), &index.to_le_bytes()],
     bump = order.bump[0],
-    realloc = Order::space(order.fills.len() as usize + 1),
-    realloc::payer = authority,
-    realloc::zero = false,
     constraint = order.outcome != outcome @ Error::InvalidOutcome,
     )]
     pub order: Box<Account<'info, Order>>,
 
+    /// CHECK: Checked and created when filling
+    #[account(mut)]
+    pub fill: UncheckedAccount<'info>,
+
     #[account(
     mut,
     constraint = event.outcome == 0 @ Error::EventSettled,
-    constraint = outcome != event.outcome @ Error::InvalidOutcome,
+    constraint = event.is_valid_outcome(outcome) @ Error::InvalidOutcome,
     )]
     pub event: Box<Account<'info, Event>>,
 
@@ -37,74 +45,236 @@ pub fn fill_order<'info>(
     outcome: u8,
     amount: u64,
 ) -> Result<()> {
+    let currency_accounts = if !is_native_mint(ctx.accounts.order.currency_mint) {
+        let remaining_accounts = &mut ctx.remaining_accounts.iter();
+        let currency_mint = next_account_info(remaining_accounts)?;
+        let order_currency_account = next_account_info(remaining_accounts)?;
+        let fill_currency_account = next_account_info(remaining_accounts)?;
+        let user_currency_account = next_account_info(remaining_accounts)?;
+        let token_program = next_account_info(remaining_accounts)?;
+        let ata_program = next_account_info(remaining_accounts)?;
+        let rent = next_account_info(remaining_accounts)?;
+
+        Some(FillCurrencyAccounts {
+            currency_mint,
+            order_currency_account,
+            fill_currency_account,
+            user_currency_account,
+            token_program,
+            ata_program,
+            rent,
+        })
+    } else {
+        None
+    };
+
+    fill(
+        ctx.program_id,
+        FillAccounts {
+            authority: &ctx.accounts.authority.to_account_info(),
+            fill_account: &ctx.accounts.fill.to_account_info(),
+            system_program: &ctx.accounts.system_program.to_account_info(),
+        },
+        &mut ctx.accounts.order,
+        &ctx.accounts.event,
+        currency_accounts,
+        outcome,
+        amount,
+    )?;
+
+    Ok(())
+}
+
+/// Accounts of the filler and the fill account it pays for
+pub struct FillAccounts<'a, 'info> {
+    pub authority: &'a AccountInfo<'info>,
+    pub fill_account: &'a AccountInfo<'info>,
+    pub system_program: &'a AccountInfo<'info>,
+}
+
+/// SPL accounts needed to fill an order that doesn't use the native mint
+pub struct FillCurrencyAccounts<'a, 'info> {
+    pub currency_mint: &'a AccountInfo<'info>,
+    pub order_currency_account: &'a AccountInfo<'info>,
+    pub fill_currency_account: &'a AccountInfo<'info>,
+    pub user_currency_account: &'a AccountInfo<'info>,
+    pub token_program: &'a AccountInfo<'info>,
+    pub ata_program: &'a AccountInfo<'info>,
+    pub rent: &'a AccountInfo<'info>,
+}
+
+/// Fills up to `amount` of the order's remaining ask for the given outcome and returns the amount
+/// actually filled after rounding to the order's odds. Creates the fill account for the order's
+/// next fill index and moves both the filled amount and the part of the order backing it there.
+///
+/// The fill only pays out if the order's or the fill's outcome wins. Any other outcome of a
+/// categorical event refunds both sides.
+pub fn fill<'info>(
+    program_id: &Pubkey,
+    accounts: FillAccounts<'_, 'info>,
+    order: &mut Account<'info, Order>,
+    event: &Account<'info, Event>,
+    currency_accounts: Option<FillCurrencyAccounts<'_, 'info>>,
+    outcome: u8,
+    amount: u64,
+) -> Result<u64> {
+    let FillAccounts { authority, fill_account, system_program } = accounts;
     let timestamp = Clock::get()?.unix_timestamp;
 
-    if ctx.accounts.order.expiry != 0 {
-        if ctx.accounts.order.expiry <= timestamp {
+    if order.expiry != 0 {
+        if order.expiry <= timestamp {
             return err!(Error::OrderExpired);
         }
     }
 
-    if ctx.accounts.event.close_time != 0 &&
-        timestamp >= ctx.accounts.event.close_time {
+    if event.close_time != 0 &&
+        timestamp >= event.close_time {
         return err!(Error::EventClosed);
     }
 
-    if amount > ctx.accounts.order.remaining_ask {
+    if amount > order.remaining_ask {
         return err!(Error::FillAmountTooLarge);
     }
 
-    let order_obligation = (amount as u128)
-        .checked_mul(10000 as u128).unwrap()
-        .checked_div(ctx.accounts.order.ask_bps as u128).unwrap() as u64;
-    // Ensure the obligation is not too large.
-    let safe_amount = (order_obligation as u128)
-        .checked_mul(ctx.accounts.order.ask_bps as u128).unwrap()
-        .checked_div(10000 as u128).unwrap() as u64;
-
-    if is_native_mint(ctx.accounts.order.currency_mint) {
-        transfer_sol(
-            &ctx.accounts.authority.to_account_info(),
-            &ctx.accounts.order.to_account_info(),
-            &ctx.accounts.system_program.to_account_info(),
-            None,
-            safe_amount,
-        )?;
-    } else {
-        let remaining_accounts = &mut ctx.remaining_accounts.iter();
-        let currency_mint = next_account_info(remaining_accounts)?;
-        let order_currency_account = next_account_info(remaining_accounts)?;
-        let user_currency_account = next_account_info(remaining_accounts)?;
-        let token_program = next_account_info(remaining_accounts)?;
+    let (safe_amount, order_amount) = order.get_fill_amounts(amount);
+    if safe_amount == 0 {
+        return err!(Error::ZeroAmount);
+    }
+
+    let order_key = order.key();
+    let fill_index = order.fill_index;
+    let fill_index_bytes = fill_index.to_le_bytes();
+    let (fill_key, fill_bump) = Pubkey::find_program_address(
+        &[b"fill".as_ref(), order_key.as_ref(), &fill_index_bytes],
+        program_id
+    );
+    if fill_key != fill_account.key() {
+        return err!(Error::InvalidFillAccount);
+    }
 
-        transfer(
-            &ctx.accounts.authority.to_account_info(),
-            &ctx.accounts.order.to_account_info(),
-            user_currency_account.into(),
-            order_currency_account.into(),
-            currency_mint.into(),
-            None,
-            None,
-            token_program.into(),
-            &ctx.accounts.system_program.to_account_info(),
-            None,
-            None,
-            None,
-            safe_amount,
-        )?;
+    // The filler pays the rent, which is refunded when the fill is settled
+    create_pda_account(
+        authority,
+        fill_account,
+        system_program,
+        program_id,
+        FILL_SIZE,
+        &[b"fill".as_ref(), order_key.as_ref(), &fill_index_bytes, &[fill_bump]],
+    )?;
+
+    let order_index_bytes = order.index.to_le_bytes();
+    match currency_accounts {
+        None => {
+            if !is_native_mint(order.currency_mint) {
+                return err!(Error::InvalidCurrencyMint);
+            }
+
+            transfer_sol(
+                authority,
+                fill_account,
+                system_program,
+                None,
+                safe_amount,
+            )?;
+            transfer_sol_pda(
+                &mut order.to_account_info(),
+                &mut fill_account.clone(),
+                order_amount,
+            )?;
+        }
+        Some(accounts) => {
+            assert_order_currency(
+                event,
+                &order_key,
+                &order.currency_mint,
+                accounts.currency_mint,
+                accounts.order_currency_account,
+            )?;
+
+            let seeds = order.auth_seeds(&order_index_bytes);
+            transfer(
+                &order.to_account_info(),
+                fill_account,
+                accounts.order_currency_account.into(),
+                accounts.fill_currency_account.into(),
+                accounts.currency_mint.into(),
+                authority.into(),
+                accounts.ata_program.into(),
+                accounts.token_program.into(),
+                system_program,
+                accounts.rent.into(),
+                Some(seeds.as_ref()),
+                None,
+                order_amount,
+            )?;
+
+            transfer(
+                authority,
+                fill_account,
+                accounts.user_currency_account.into(),
+                accounts.fill_currency_account.into(),
+                accounts.currency_mint.into(),
+                None,
+                None,
+                accounts.token_program.into(),
+                system_program,
+                None,
+                None,
+                None,
+                safe_amount,
+            )?;
+        }
     }
 
-    let fill_index = ctx.accounts.order.fills.len() as u32;
-    let order = &mut ctx.accounts.order;
+    let fill_data = Fill {
+        bump: [fill_bump],
+        order: order_key,
+        index: fill_index,
+        authority: authority.key(),
+        outcome,
+        amount: safe_amount,
+        order_amount,
+        event: event.key(),
+        order_authority: order.authority,
+        order_outcome: order.outcome,
+        currency_mint: order.currency_mint,
+    };
+    let mut data = fill_account.try_borrow_mut_data()?;
+    let mut writer: &mut [u8] = &mut data[..];
+    fill_data.try_serialize(&mut writer)?;
+
+    order.fill_index = order.fill_index.checked_add(1)
+        .ok_or(Error::OverflowError)?;
     order.remaining_ask = order.remaining_ask.checked_sub(safe_amount)
         .ok_or(Error::OverflowError)?;
-    order.fills.push(Fill {
-        index: fill_index,
-        authority: ctx.accounts.authority.key(),
+    order.amount = order.amount.checked_sub(order_amount)
+        .ok_or(Error::OverflowError)?;
+
+    emit!(OrderFilled {
+        event: event.key(),
+        order: order_key,
+        fill: fill_key,
+        authority: authority.key(),
+        fill_index,
         outcome,
         amount: safe_amount,
-        is_settled: false,
+        ask_bps: order.ask_bps,
+        remaining_ask: order.remaining_ask,
     });
 
-    Ok(())
-}
\ No newline at end of file
+    Ok(safe_amount)
+}
+
+#[event]
+pub struct OrderFilled {
+    pub event: Pubkey,
+    pub order: Pubkey,
+    pub fill: Pubkey,
+    /// Account that filled the order
+    pub authority: Pubkey,
+    pub fill_index: u32,
+    pub outcome: u8,
+    pub amount: u64,
+    pub ask_bps: u32,
+    pub remaining_ask: u64,
+}
@@ -0,0 +1,196 @@
+use anchor_lang::prelude::*;
+use crate::state::{Event, Order};
+use crate::error::Error;
+use crate::instructions::{fill, FillAccounts, FillCurrencyAccounts};
+use crate::util::is_native_mint;
+
+#[derive(Accounts)]
+#[instruction(outcome: u8)]
+pub struct MarketFill<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    #[account(
+    constraint = event.outcome == 0 @ Error::EventSettled,
+    constraint = event.is_valid_outcome(outcome) @ Error::InvalidOutcome,
+    )]
+    pub event: Box<Account<'info, Event>>,
+
+    pub system_program: Program<'info, System>,
+    pub rent: Sysvar<'info, Rent>,
+}
+
+/// Fills up to `amount` across the orders passed in remaining_accounts, best odds first, skipping
+/// any order with an ask above `max_ask_bps`. All orders must use the same currency mint.
+///
+/// remaining_accounts are the `num_orders` orders, then the account for the next fill of each
+/// order, followed by, for SPL orders, the currency mint, the user currency account, the token
+/// program, the ATA program, rent, and the currency accounts of each order and then of each fill.
+/// Per order accounts are in the same order as the orders.
+pub fn market_fill<'info>(
+    ctx: Context<'_, '_, '_, 'info, MarketFill<'info>>,
+    outcome: u8,
+    amount: u64,
+    max_ask_bps: u32,
+    num_orders: u8,
+) -> Result<()> {
+    let num_orders = num_orders as usize;
+    if num_orders == 0 || ctx.remaining_accounts.len() < num_orders * 2 {
+        return err!(Error::NoOrdersFilled);
+    }
+
+    let event_key = ctx.accounts.event.key();
+    let mut orders = Vec::with_capacity(num_orders);
+    for order_info in &ctx.remaining_accounts[..num_orders] {
+        let order = Account::<'info, Order>::try_from(order_info)?;
+        let is_duplicate = orders.iter().any(|other: &Account<Order>| other.key() == order.key());
+        if order.event != event_key || is_duplicate {
+            return err!(Error::InvalidOrder);
+        }
+
+        orders.push(order);
+    }
+
+    let currency_mint = orders[0].currency_mint;
+    if orders.iter().any(|order| order.currency_mint != currency_mint) {
+        return err!(Error::InvalidCurrencyMint);
+    }
+
+    let fill_accounts = &ctx.remaining_accounts[num_orders..num_orders * 2];
+    let remaining_accounts = &mut ctx.remaining_accounts[num_orders * 2..].iter();
+    let spl_accounts = if !is_native_mint(currency_mint) {
+        let currency_mint = next_account_info(remaining_accounts)?;
+        let user_currency_account = next_account_info(remaining_accounts)?;
+        let token_program = next_account_info(remaining_accounts)?;
+        let ata_program = next_account_info(remaining_accounts)?;
+        let rent = next_account_info(remaining_accounts)?;
+        let mut order_currency_accounts = Vec::with_capacity(num_orders);
+        for _ in 0..num_orders {
+            order_currency_accounts.push(next_account_info(remaining_accounts)?);
+        }
+        let mut fill_currency_accounts = Vec::with_capacity(num_orders);
+        for _ in 0..num_orders {
+            fill_currency_accounts.push(next_account_info(remaining_accounts)?);
+        }
+
+        Some((
+            currency_mint,
+            user_currency_account,
+            token_program,
+            ata_program,
+            rent,
+            order_currency_accounts,
+            fill_currency_accounts
+        ))
+    } else {
+        None
+    };
+
+    let timestamp = Clock::get()?.unix_timestamp;
+    let asks: Vec<(u32, u64)> = orders.iter().map(|order| {
+        let is_fillable = order.outcome != outcome &&
+            (order.expiry == 0 || order.expiry > timestamp);
+        (order.ask_bps, if is_fillable { order.remaining_ask } else { 0 })
+    }).collect();
+
+    let mut total_filled: u64 = 0;
+    for (position, fill_amount) in get_market_fills(&asks, amount, max_ask_bps) {
+        // Too small to fill at this order's odds
+        if orders[position].get_fill_amounts(fill_amount).0 == 0 {
+            continue;
+        }
+
+        let currency_accounts = spl_accounts.as_ref().map(|(
+            currency_mint,
+            user_currency_account,
+            token_program,
+            ata_program,
+            rent,
+            order_currency_accounts,
+            fill_currency_accounts
+        )| FillCurrencyAccounts {
+            currency_mint,
+            order_currency_account: order_currency_accounts[position],
+            fill_currency_account: fill_currency_accounts[position],
+            user_currency_account,
+            token_program,
+            ata_program,
+            rent,
+        });
+
+        let filled = fill(
+            ctx.program_id,
+            FillAccounts {
+                authority: &ctx.accounts.authority.to_account_info(),
+                fill_account: &fill_accounts[position],
+                system_program: &ctx.accounts.system_program.to_account_info(),
+            },
+            &mut orders[position],
+            &ctx.accounts.event,
+            currency_accounts,
+            outcome,
+            fill_amount,
+        )?;
+        total_filled = total_filled.checked_add(filled)
+            .ok_or(Error::OverflowError)?;
+    }
+
+    if total_filled == 0 {
+        return err!(Error::NoOrdersFilled);
+    }
+
+    // Orders from remaining_accounts aren't written back by anchor
+    for order in &orders {
+        order.exit(ctx.program_id)?;
+    }
+
+    Ok(())
+}
+
+/// Positions of the orders to fill, cheapest ask first, with the amount to fill from each. Asks
+/// are (ask_bps, fillable amount) and any ask above `max_ask_bps` is skipped.
+fn get_market_fills(asks: &[(u32, u64)], amount: u64, max_ask_bps: u32) -> Vec<(usize, u64)> {
+    let mut positions: Vec<usize> = (0..asks.len()).collect();
+    positions.sort_by_key(|position| asks[*position].0);
+
+    let mut fills = Vec::new();
+    let mut remaining = amount;
+    for position in positions {
+        let (ask_bps, fillable) = asks[position];
+        if remaining == 0 || ask_bps > max_ask_bps {
+            break;
+        }
+
+        let fill_amount = remaining.min(fillable);
+        if fill_amount > 0 {
+            fills.push((position, fill_amount));
+            remaining -= fill_amount;
+        }
+    }
+
+    fills
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::instructions::market_fill::get_market_fills;
+
+    #[test]
+    fn market_fills_best_odds_first() {
+        let asks = [(9000, 100), (5000, 50), (7000, 100)];
+        assert_eq!(vec![(1, 50), (2, 100), (0, 20)], get_market_fills(&asks, 170, 10000));
+    }
+
+    #[test]
+    fn market_fills_respect_max_ask() {
+        let asks = [(9000, 100), (5000, 50), (7000, 100)];
+        assert_eq!(vec![(1, 50), (2, 100)], get_market_fills(&asks, 500, 7000));
+        assert_eq!(Vec::<(usize, u64)>::new(), get_market_fills(&asks, 500, 4000));
+    }
+
+    #[test]
+    fn market_fills_skip_unfillable() {
+        let asks = [(5000, 0), (6000, 30)];
+        assert_eq!(vec![(1, 30)], get_market_fills(&asks, 100, 10000));
+    }
+}
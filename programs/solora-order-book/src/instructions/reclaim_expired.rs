@@ -0,0 +1,118 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token;
+use anchor_spl::token::{CloseAccount, TokenAccount};
+use crate::state::{Event, Order};
+use crate::error::Error;
+use crate::instructions::OrderCancelled;
+use crate::util::{assert_order_currency, is_native_mint, transfer};
+
+#[derive(Accounts)]
+#[instruction(index: u32)]
+pub struct ReclaimExpired<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    /// CHECK: Safe due to order constraint
+    #[account(mut)]
+    pub authority: UncheckedAccount<'info>,
+
+    #[account(
+    mut,
+    seeds = [b"order".as_ref(), event.key().as_ref(), &index.to_le_bytes()],
+    bump = order.bump[0],
+    has_one = authority,
+    )]
+    pub order: Box<Account<'info, Order>>,
+
+    #[account()]
+    pub event: Box<Account<'info, Event>>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/// Returns the part of the order amount backing the unfilled ask to the maker and closes the order
+/// once it can no longer be filled, either because it expired or was completely filled. Anyone can
+/// call this so funds and rent aren't locked until the event is settled.
+pub fn reclaim_expired<'info>(
+    ctx: Context<'_, '_, '_, 'info, ReclaimExpired<'info>>,
+    index: u32,
+) -> Result<()> {
+    let timestamp = Clock::get()?.unix_timestamp;
+    let order_expired = ctx.accounts.order.expiry != 0 && ctx.accounts.order.expiry <= timestamp;
+    let event_closed = ctx.accounts.event.outcome != 0 ||
+        (ctx.accounts.event.close_time != 0 && timestamp >= ctx.accounts.event.close_time);
+    let order_filled = ctx.accounts.order.remaining_ask == 0;
+    if !order_expired && !event_closed && !order_filled {
+        return err!(Error::OrderNotExpired);
+    }
+
+    // Fills hold their own funds and a copy of the order so everything left in the order backs
+    // the unfilled ask and can be returned by closing the order account(s)
+    if !is_native_mint(ctx.accounts.order.currency_mint) {
+        let remaining_accounts = &mut ctx.remaining_accounts.iter();
+        let currency_mint = next_account_info(remaining_accounts)?;
+        let order_currency_account = next_account_info(remaining_accounts)?;
+        let authority_currency_account = next_account_info(remaining_accounts)?;
+        let token_program = next_account_info(remaining_accounts)?;
+        let ata_program = next_account_info(remaining_accounts)?;
+        let rent = next_account_info(remaining_accounts)?;
+
+        assert_order_currency(
+            &ctx.accounts.event,
+            &ctx.accounts.order.key(),
+            &ctx.accounts.order.currency_mint,
+            currency_mint,
+            order_currency_account,
+        )?;
+
+        let index_bytes = &index.to_le_bytes();
+        let seeds = ctx.accounts.order.auth_seeds(index_bytes);
+        let auth_seeds = seeds.as_ref();
+
+        let refund_amount =
+            Account::<'info, TokenAccount>::try_from(order_currency_account)?.amount;
+
+        if refund_amount > 0 {
+            transfer(
+                &ctx.accounts.order.to_account_info(),
+                &ctx.accounts.authority.to_account_info(),
+                order_currency_account.into(),
+                authority_currency_account.into(),
+                currency_mint.into(),
+                Option::from(&ctx.accounts.payer.to_account_info()),
+                ata_program.into(),
+                token_program.into(),
+                &ctx.accounts.system_program.to_account_info(),
+                rent.into(),
+                auth_seeds.into(),
+                None,
+                refund_amount,
+            )?;
+        }
+
+        token::close_account(
+            CpiContext::new(
+                token_program.to_account_info(),
+                CloseAccount {
+                    account: order_currency_account.to_account_info(),
+                    destination: ctx.accounts.authority.to_account_info(),
+                    authority: ctx.accounts.order.to_account_info(),
+                },
+            ).with_signer(&[auth_seeds]),
+        )?;
+    }
+
+    emit!(OrderCancelled {
+        event: ctx.accounts.event.key(),
+        order: ctx.accounts.order.key(),
+        authority: ctx.accounts.authority.key(),
+        amount: ctx.accounts.order.remaining_ask,
+        refund_amount: ctx.accounts.order.amount,
+        remaining_ask: 0,
+        closed: true,
+    });
+
+    ctx.accounts.order.close(ctx.accounts.authority.to_account_info())?;
+
+    Ok(())
+}
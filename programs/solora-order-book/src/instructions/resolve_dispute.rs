@@ -0,0 +1,81 @@
+use anchor_lang::prelude::*;
+use crate::state::{Dispute, Event};
+use crate::error::Error;
+use crate::instructions::EventResolved;
+use crate::util::transfer_sol_pda;
+
+#[derive(Accounts)]
+#[instruction(outcome: u8)]
+pub struct ResolveDispute<'info> {
+    pub arbiter: Signer<'info>,
+
+    /// CHECK: Safe due to dispute constraint
+    #[account(mut)]
+    pub authority: UncheckedAccount<'info>,
+
+    /// CHECK: Safe due to event constraint
+    #[account(mut)]
+    pub fee_account: UncheckedAccount<'info>,
+
+    #[account(
+    mut,
+    seeds = [b"event".as_ref(), event.id.as_ref()],
+    bump = event.bump[0],
+    has_one = arbiter,
+    has_one = fee_account,
+    constraint = event.is_disputed @ Error::EventNotDisputed,
+    constraint = event.is_valid_result(outcome) @ Error::InvalidOutcome,
+    )]
+    pub event: Box<Account<'info, Event>>,
+
+    #[account(
+    mut,
+    close = authority,
+    seeds = [b"dispute".as_ref(), event.key().as_ref()],
+    bump = dispute.bump[0],
+    has_one = event,
+    has_one = authority,
+    )]
+    pub dispute: Box<Account<'info, Dispute>>,
+}
+
+/// Sets the final outcome of a disputed event before the arbiter deadline. The bond is returned to
+/// the disputer if the settled outcome is overturned, otherwise it's slashed to the event fee
+/// account.
+pub fn resolve_dispute<'info>(
+    ctx: Context<'_, '_, '_, 'info, ResolveDispute<'info>>,
+    outcome: u8,
+) -> Result<()> {
+    let event = &mut ctx.accounts.event;
+
+    let timestamp = Clock::get()?.unix_timestamp;
+    if timestamp >= event.arbiter_deadline() {
+        return err!(Error::ArbiterPeriodExpired);
+    }
+
+    let is_overturned = outcome != event.outcome;
+
+    event.outcome = outcome;
+    event.is_disputed = false;
+    event.finalized = true;
+
+    emit!(EventResolved {
+        event: event.key(),
+        resolver: ctx.accounts.arbiter.key(),
+        outcome,
+        settle_time: event.settle_time,
+        finalized: true,
+    });
+
+    // The remaining lamports, including the bond if not slashed, go back to the disputer on close
+    let bond = ctx.accounts.dispute.bond;
+    if !is_overturned && bond > 0 {
+        transfer_sol_pda(
+            &mut ctx.accounts.dispute.to_account_info(),
+            &mut ctx.accounts.fee_account.to_account_info(),
+            bond,
+        )?;
+    }
+
+    Ok(())
+}
@@ -0,0 +1,169 @@
+use anchor_lang::prelude::*;
+use pyth_sdk_solana::Price;
+use pyth_sdk_solana::state::{load_price_account, PriceStatus};
+use crate::state::{Comparison, Event, ORACLE_PUBLISH_TOLERANCE, ORACLE_RESOLVE_PERIOD};
+use crate::instructions::EventResolved;
+use crate::error::Error;
+use crate::util::get_price_with_decimal_change;
+
+#[derive(Accounts)]
+pub struct ResolveWithOracle<'info> {
+    #[account(
+    mut,
+    seeds = [b"event".as_ref(), event.id.as_ref()],
+    bump = event.bump[0],
+    has_one = pyth_feed,
+    constraint = event.outcome == 0 @ Error::EventSettled,
+    constraint = event.is_oracle_event() @ Error::NotOracleEvent,
+    )]
+    pub event: Box<Account<'info, Event>>,
+
+    /// CHECK: Safe due to event constraint
+    pub pyth_feed: UncheckedAccount<'info>,
+}
+
+/// Resolves an oracle event from the first feed price published at or after its close time, as
+/// long as it was published within `ORACLE_PUBLISH_TOLERANCE` seconds of closing.
+pub fn resolve_with_oracle<'info>(
+    ctx: Context<'_, '_, '_, 'info, ResolveWithOracle<'info>>,
+) -> Result<()> {
+    let event = &mut ctx.accounts.event;
+
+    let timestamp = Clock::get()?.unix_timestamp;
+    if timestamp < event.close_time {
+        return err!(Error::EventNotClosed);
+    }
+
+    if timestamp - event.close_time > ORACLE_RESOLVE_PERIOD {
+        msg!("Resolve period expired: {} {}", timestamp, event.close_time);
+        return err!(Error::OracleResolvePeriodExpired);
+    }
+
+    let data = ctx.accounts.pyth_feed.try_borrow_data()?;
+    let price_account = load_price_account(&data)
+        .map_err(|_| Error::InvalidPrice)?;
+
+    // The account keeps the current aggregate and the previous one
+    let mut prices = vec![Price {
+        price: price_account.prev_price,
+        conf: price_account.prev_conf,
+        expo: price_account.expo,
+        publish_time: price_account.prev_timestamp,
+    }];
+    if price_account.agg.status == PriceStatus::Trading {
+        prices.push(Price {
+            price: price_account.agg.price,
+            conf: price_account.agg.conf,
+            expo: price_account.expo,
+            publish_time: price_account.timestamp,
+        });
+    }
+
+    let price = get_close_price(&prices, event.close_time, ORACLE_PUBLISH_TOLERANCE)
+        .ok_or(Error::InvalidPrice)?;
+    msg!("publish time: {}", price.publish_time);
+
+    if price.price < 0 {
+        msg!("Negative price: {}", price.price);
+        return err!(Error::InvalidPrice);
+    }
+
+    let price = get_price_with_decimal_change(price.price, price.expo, event.price_decimals)?;
+    msg!("price: {}", price);
+
+    event.outcome = get_oracle_outcome(
+        event.comparison,
+        price,
+        event.strike_price,
+        event.strike_price_high
+    );
+    event.settle_time = timestamp;
+    // Outcomes read from the feed aren't open to disputes
+    event.finalized = true;
+
+    emit!(EventResolved {
+        event: event.key(),
+        resolver: event.pyth_feed,
+        outcome: event.outcome,
+        settle_time: timestamp,
+        finalized: true,
+    });
+
+    Ok(())
+}
+
+/// First price published at or after `close_time` if within `tolerance` seconds of it
+fn get_close_price(prices: &[Price], close_time: i64, tolerance: i64) -> Option<Price> {
+    prices.iter()
+        .filter(|price| {
+            price.publish_time >= close_time && price.publish_time - close_time <= tolerance
+        })
+        .min_by_key(|price| price.publish_time)
+        .copied()
+}
+
+fn get_oracle_outcome(
+    comparison: Comparison,
+    price: u64,
+    strike_price: u64,
+    strike_price_high: u64
+) -> u8 {
+    let is_true = match comparison {
+        Comparison::Above => price > strike_price,
+        Comparison::Below => price < strike_price,
+        Comparison::Between => price >= strike_price && price <= strike_price_high,
+        Comparison::None => false,
+    };
+
+    if is_true {
+        1
+    } else {
+        2
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::instructions::resolve_with_oracle::{get_close_price, get_oracle_outcome};
+    use crate::state::Comparison;
+    use pyth_sdk_solana::Price;
+
+    fn price_at(publish_time: i64) -> Price {
+        Price { price: 100, conf: 1, expo: -2, publish_time }
+    }
+
+    #[test]
+    fn oracle_outcome_above() {
+        assert_eq!(1, get_oracle_outcome(Comparison::Above, 101, 100, 0));
+        assert_eq!(2, get_oracle_outcome(Comparison::Above, 100, 100, 0));
+    }
+
+    #[test]
+    fn oracle_outcome_below() {
+        assert_eq!(1, get_oracle_outcome(Comparison::Below, 99, 100, 0));
+        assert_eq!(2, get_oracle_outcome(Comparison::Below, 100, 100, 0));
+    }
+
+    #[test]
+    fn oracle_outcome_between() {
+        assert_eq!(1, get_oracle_outcome(Comparison::Between, 100, 100, 200));
+        assert_eq!(1, get_oracle_outcome(Comparison::Between, 200, 100, 200));
+        assert_eq!(2, get_oracle_outcome(Comparison::Between, 99, 100, 200));
+        assert_eq!(2, get_oracle_outcome(Comparison::Between, 201, 100, 200));
+    }
+
+    #[test]
+    fn close_price_first_after_close() {
+        let prices = [price_at(99), price_at(101)];
+        assert_eq!(Some(price_at(101)), get_close_price(&prices, 100, 10));
+
+        let prices = [price_at(100), price_at(103)];
+        assert_eq!(Some(price_at(100)), get_close_price(&prices, 100, 10));
+    }
+
+    #[test]
+    fn close_price_outside_tolerance() {
+        let prices = [price_at(95), price_at(111)];
+        assert_eq!(None, get_close_price(&prices, 100, 10));
+    }
+}
@@ -1,5 +1,5 @@
 use anchor_lang::prelude::*;
-use crate::state::{Event};
+use crate::state::{Event, ORACLE_RESOLVE_PERIOD};
 use crate::error::Error;
 
 #[derive(Accounts)]
@@ -14,7 +14,7 @@ pub struct SettleEvent<'info> {
     bump = event.bump[0],
     has_one = authority,
     constraint = event.outcome == 0 @ Error::EventSettled,
-    constraint = outcome != 0 @ Error::InvalidOutcome,
+    constraint = event.is_valid_result(outcome) @ Error::InvalidOutcome,
     )]
     pub event: Box<Account<'info, Event>>,
 
@@ -27,7 +27,37 @@ pub fn settle_event<'info>(
     outcome: u8,
 ) -> Result<()> {
     let event = &mut ctx.accounts.event;
+
+    // The authority can only settle an oracle event if nobody resolved it from the feed in time
+    let timestamp = Clock::get()?.unix_timestamp;
+    if event.is_oracle_event() && timestamp <= event.close_time + ORACLE_RESOLVE_PERIOD {
+        return err!(Error::OracleResolvePeriodActive);
+    }
+
     event.outcome = outcome;
+    event.settle_time = timestamp;
+    // Without a dispute period the outcome can't be challenged
+    event.finalized = event.dispute_period == 0;
+
+    emit!(EventResolved {
+        event: event.key(),
+        resolver: ctx.accounts.authority.key(),
+        outcome,
+        settle_time: timestamp,
+        finalized: event.finalized,
+    });
 
     Ok(())
+}
+
+/// Emitted whenever an event's outcome is set, whether by the authority, the oracle or the arbiter
+#[event]
+pub struct EventResolved {
+    pub event: Pubkey,
+    /// Account that set the outcome or the pyth feed for oracle events
+    pub resolver: Pubkey,
+    pub outcome: u8,
+    pub settle_time: i64,
+    /// False if the outcome can still be disputed
+    pub finalized: bool,
 }
\ No newline at end of file
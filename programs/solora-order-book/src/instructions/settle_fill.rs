@@ -1,23 +1,22 @@
 use anchor_lang::prelude::*;
-use crate::state::{Event, Order};
+use anchor_spl::token;
+use anchor_spl::token::{CloseAccount, TokenAccount};
+use crate::state::{Event, Fill};
 use crate::error::Error;
-use crate::util::{is_native_mint, transfer};
+use crate::util::{assert_order_currency, is_native_mint, transfer, transfer_sol_pda};
 
 #[derive(Accounts)]
-#[instruction(order_index: u32, fill_index: u32)]
+#[instruction(fill_index: u32)]
 pub struct SettleFill<'info> {
     #[account(mut)]
     pub payer: Signer<'info>,
 
-    /// CHECK: Safe due to order constraint
+    /// CHECK: Safe due to fill constraint
     #[account(mut)]
     pub authority: UncheckedAccount<'info>,
 
-    /// CHECK: Safe due to constraint
-    #[account(
-    mut,
-    constraint = order.fills[fill_index as usize].authority == authority.key() @ Error::InvalidFillAuthority,
-    )]
+    /// CHECK: Safe due to fill constraint
+    #[account(mut)]
     pub fill_authority: UncheckedAccount<'info>,
 
     /// CHECK: Safe due to event constraint
@@ -26,14 +25,16 @@ pub struct SettleFill<'info> {
 
     #[account(
     mut,
-    seeds = [b"order".as_ref(), event.key().as_ref(), &order_index.to_le_bytes()],
-    bump = order.bump[0],
-    has_one = authority
+    close = fill_authority,
+    seeds = [b"fill".as_ref(), fill.order.as_ref(), &fill_index.to_le_bytes()],
+    bump = fill.bump[0],
+    has_one = event,
+    constraint = fill.order_authority == authority.key() @ Error::InvalidOrderAuthority,
+    constraint = fill.authority == fill_authority.key() @ Error::InvalidFillAuthority
     )]
-    pub order: Box<Account<'info, Order>>,
+    pub fill: Box<Account<'info, Fill>>,
 
     #[account(
-    mut,
     constraint = event.outcome != 0 @ Error::EventNotSettled,
     has_one = fee_account
     )]
@@ -43,73 +44,41 @@ pub struct SettleFill<'info> {
     pub rent: Sysvar<'info, Rent>,
 }
 
+/// Pays out both sides of a fill from the fill account and closes it, refunding its rent to the
+/// fill authority. The fill holds everything it needs from the order, so fills can be settled in
+/// parallel and after the order is closed.
+///
+/// A fill is a bet between the order's outcome and the fill's outcome only. On categorical events
+/// a win by any other outcome is a draw for the fill and refunds both sides, the same as a void.
 pub fn settle_fill<'info>(
     ctx: Context<'_, '_, '_, 'info, SettleFill<'info>>,
-    order_index: u32,
     fill_index: u32
 ) -> Result<()> {
-    if fill_index > ctx.accounts.order.fills.len() as u32 - 1 {
-        return err!(Error::FillNotFound);
-    }
-
-    let order = &mut ctx.accounts.order;
-    let order_clone = order.clone();
-    let fill = &order.fills[fill_index as usize];
-    if fill.is_settled {
-        return err!(Error::FillAlreadySettled);
+    let timestamp = Clock::get()?.unix_timestamp;
+    if !ctx.accounts.event.is_finalized(timestamp) {
+        return err!(Error::EventNotFinalized);
     }
 
-    let is_native = is_native_mint(order_clone.currency_mint);
-    let index_bytes = &order_index.to_le_bytes();
-    let seeds = order.auth_seeds(index_bytes);
+    let fill = &ctx.accounts.fill;
+    let is_native = is_native_mint(fill.currency_mint);
+    let index_bytes = &fill_index.to_le_bytes();
+    let seeds = fill.auth_seeds(index_bytes);
     let auth_seeds = seeds.as_ref();
 
-    // TODO: Remove fill from list and realloc, paying back the authority
-
-    let is_order_winner = order.outcome == ctx.accounts.event.outcome;
-    let is_draw = !is_order_winner && fill.outcome != ctx.accounts.event.outcome;
-
-    let order_obligation = (fill.amount as u128)
-        .checked_mul(10000 as u128).unwrap()
-        .checked_div(order.ask_bps as u128).unwrap() as u64;
-
-    let mut amount_to_order_authority = if is_order_winner {
-        order_obligation.checked_add(fill.amount).unwrap()
-    } else if is_draw {
-        order_obligation
-    } else {
-        0
-    };
-
-    let mut amount_to_fill_authority = if is_order_winner {
-        0
-    } else if is_draw {
-        fill.amount
-    } else {
-        order_obligation.checked_add(fill.amount).unwrap()
-    };
-
-    let fee = if is_order_winner {
-        (fill.amount as u128).checked_mul(ctx.accounts.event.fee_bps as u128).unwrap()
-            .checked_div(10000 as u128).unwrap() as u64
-    } else if is_draw {
-        0
-    } else {
-        (order_obligation as u128).checked_mul(ctx.accounts.event.fee_bps as u128).unwrap()
-            .checked_div(10000 as u128).unwrap() as u64
-    };
+    let (amount_to_order_authority, amount_to_fill_authority, fee) =
+        fill.get_payouts(ctx.accounts.event.outcome, ctx.accounts.event.fee_bps);
 
     let remaining_accounts = &mut ctx.remaining_accounts.iter();
     let (
         currency_mint,
-        order_currency_account,
+        fill_currency_account,
         authority_currency_account,
         fee_currency_account,
         fill_authority_currency_account,
         token_program,
         ata_program,
         rent
-    ) = if is_native {
+    ) = if !is_native {
         (
             Option::from(next_account_info(remaining_accounts)?),
             Option::from(next_account_info(remaining_accounts)?),
@@ -133,16 +102,28 @@ pub fn settle_fill<'info>(
         )
     };
 
-    if amount_to_order_authority > 0 {
-        if fee > 0 {
-            amount_to_order_authority = amount_to_order_authority.checked_sub(fee).unwrap();
-        }
+    if !is_native {
+        assert_order_currency(
+            &ctx.accounts.event,
+            &fill.key(),
+            &fill.currency_mint,
+            currency_mint.unwrap(),
+            fill_currency_account.unwrap(),
+        )?;
+    }
 
-        if amount_to_order_authority > 0 {
+    if amount_to_order_authority > 0 {
+        if is_native {
+            transfer_sol_pda(
+                &mut fill.to_account_info(),
+                &mut ctx.accounts.authority.to_account_info(),
+                amount_to_order_authority,
+            )?;
+        } else {
             transfer(
-                &order_clone.to_account_info(),
+                &fill.to_account_info(),
                 &ctx.accounts.authority.to_account_info(),
-                order_currency_account,
+                fill_currency_account,
                 authority_currency_account,
                 currency_mint,
                 Option::from(&ctx.accounts.payer.to_account_info()),
@@ -158,15 +139,17 @@ pub fn settle_fill<'info>(
     }
 
     if amount_to_fill_authority > 0 {
-        if fee > 0 {
-            amount_to_fill_authority = amount_to_fill_authority.checked_sub(fee).unwrap();
-        }
-
-        if amount_to_fill_authority > 0 {
+        if is_native {
+            transfer_sol_pda(
+                &mut fill.to_account_info(),
+                &mut ctx.accounts.fill_authority.to_account_info(),
+                amount_to_fill_authority,
+            )?;
+        } else {
             transfer(
-                &order_clone.to_account_info(),
+                &fill.to_account_info(),
                 &ctx.accounts.fill_authority.to_account_info(),
-                order_currency_account,
+                fill_currency_account,
                 fill_authority_currency_account,
                 currency_mint,
                 Option::from(&ctx.accounts.payer.to_account_info()),
@@ -182,25 +165,98 @@ pub fn settle_fill<'info>(
     }
 
     if fee > 0 {
-        transfer(
-            &order_clone.to_account_info(),
-            &ctx.accounts.fee_account.to_account_info(),
-            order_currency_account,
-            fee_currency_account,
-            currency_mint,
-            Option::from(&ctx.accounts.payer.to_account_info()),
-            ata_program,
-            token_program,
-            &ctx.accounts.system_program.to_account_info(),
-            rent,
-            auth_seeds.into(),
-            None,
-            fee,
-        )?;
+        if is_native {
+            transfer_sol_pda(
+                &mut fill.to_account_info(),
+                &mut ctx.accounts.fee_account.to_account_info(),
+                fee,
+            )?;
+        } else {
+            transfer(
+                &fill.to_account_info(),
+                &ctx.accounts.fee_account.to_account_info(),
+                fill_currency_account,
+                fee_currency_account,
+                currency_mint,
+                Option::from(&ctx.accounts.payer.to_account_info()),
+                ata_program,
+                token_program,
+                &ctx.accounts.system_program.to_account_info(),
+                rent,
+                auth_seeds.into(),
+                None,
+                fee,
+            )?;
+        }
     }
 
-    let fill = &mut order.fills[fill_index as usize];
-    fill.is_settled = true;
+    emit!(FillSettled {
+        event: ctx.accounts.event.key(),
+        order: fill.order,
+        fill: fill.key(),
+        fill_index,
+        authority: fill.order_authority,
+        fill_authority: fill.authority,
+        outcome: ctx.accounts.event.outcome,
+        amount_to_order_authority,
+        amount_to_fill_authority,
+        fee,
+    });
+
+    // Close the fill currency account back to the filler who paid for it. The fill account itself
+    // is closed to the filler by anchor.
+    if !is_native {
+        let fill_currency_account = fill_currency_account.unwrap();
+        let token_program = token_program.unwrap();
+
+        // Anything sent to the fill currency account from elsewhere would block closing it
+        let token_account =
+            Account::<'info, TokenAccount>::try_from(fill_currency_account)?;
+        if token_account.amount > 0 {
+            transfer(
+                &fill.to_account_info(),
+                &ctx.accounts.fill_authority.to_account_info(),
+                fill_currency_account.into(),
+                fill_authority_currency_account,
+                currency_mint,
+                Option::from(&ctx.accounts.payer.to_account_info()),
+                ata_program,
+                token_program.into(),
+                &ctx.accounts.system_program.to_account_info(),
+                rent,
+                auth_seeds.into(),
+                None,
+                token_account.amount,
+            )?;
+        }
+
+        token::close_account(
+            CpiContext::new(
+                token_program.to_account_info(),
+                CloseAccount {
+                    account: fill_currency_account.to_account_info(),
+                    destination: ctx.accounts.fill_authority.to_account_info(),
+                    authority: fill.to_account_info(),
+                },
+            ).with_signer(&[auth_seeds]),
+        )?;
+    }
 
     Ok(())
-}
\ No newline at end of file
+}
+
+#[event]
+pub struct FillSettled {
+    pub event: Pubkey,
+    pub order: Pubkey,
+    pub fill: Pubkey,
+    pub fill_index: u32,
+    /// Order authority
+    pub authority: Pubkey,
+    pub fill_authority: Pubkey,
+    pub outcome: u8,
+    /// Payouts after fees
+    pub amount_to_order_authority: u64,
+    pub amount_to_fill_authority: u64,
+    pub fee: u64,
+}
@@ -1,5 +1,6 @@
 use anchor_lang::prelude::*;
 use instructions::*;
+use state::CreateEventParams;
 
 mod state;
 mod error;
@@ -15,12 +16,9 @@ pub mod solora_order_book {
     pub fn create_event<'info>(
         ctx: Context<'_, '_, '_, 'info, CreateEvent<'info>>,
         id: [u8; 32],
-        fee_account: Pubkey,
-        fee_bps: u32,
-        end_time: i64,
-        metadata_uri: String,
+        params: CreateEventParams,
     ) -> Result<()> {
-        instructions::create_event(ctx, id, fee_account, fee_bps, end_time, metadata_uri)
+        instructions::create_event(ctx, id, params)
     }
 
     pub fn create_order<'info>(
@@ -41,6 +39,13 @@ pub mod solora_order_book {
         instructions::cancel_order(ctx, index, amount)
     }
 
+    pub fn reclaim_expired<'info>(
+        ctx: Context<'_, '_, '_, 'info, ReclaimExpired<'info>>,
+        index: u32
+    ) -> Result<()> {
+        instructions::reclaim_expired(ctx, index)
+    }
+
     pub fn fill_order<'info>(
         ctx: Context<'_, '_, '_, 'info, FillOrder<'info>>,
         index: u32,
@@ -50,6 +55,16 @@ pub mod solora_order_book {
         instructions::fill_order(ctx, index, outcome, amount)
     }
 
+    pub fn market_fill<'info>(
+        ctx: Context<'_, '_, '_, 'info, MarketFill<'info>>,
+        outcome: u8,
+        amount: u64,
+        max_ask_bps: u32,
+        num_orders: u8
+    ) -> Result<()> {
+        instructions::market_fill(ctx, outcome, amount, max_ask_bps, num_orders)
+    }
+
     pub fn settle_event<'info>(
         ctx: Context<'_, '_, '_, 'info, SettleEvent<'info>>,
         id: [u8; 32],
@@ -58,11 +73,36 @@ pub mod solora_order_book {
         instructions::settle_event(ctx, id, outcome)
     }
 
+    pub fn resolve_with_oracle<'info>(
+        ctx: Context<'_, '_, '_, 'info, ResolveWithOracle<'info>>,
+    ) -> Result<()> {
+        instructions::resolve_with_oracle(ctx)
+    }
+
+    pub fn dispute_event<'info>(
+        ctx: Context<'_, '_, '_, 'info, DisputeEvent<'info>>,
+        outcome: u8
+    ) -> Result<()> {
+        instructions::dispute_event(ctx, outcome)
+    }
+
+    pub fn resolve_dispute<'info>(
+        ctx: Context<'_, '_, '_, 'info, ResolveDispute<'info>>,
+        outcome: u8
+    ) -> Result<()> {
+        instructions::resolve_dispute(ctx, outcome)
+    }
+
+    pub fn expire_dispute<'info>(
+        ctx: Context<'_, '_, '_, 'info, ExpireDispute<'info>>,
+    ) -> Result<()> {
+        instructions::expire_dispute(ctx)
+    }
+
     pub fn settle_fill<'info>(
         ctx: Context<'_, '_, '_, 'info, SettleFill<'info>>,
-        order_index: u32,
         fill_index: u32
     ) -> Result<()> {
-        instructions::settle_fill(ctx, order_index, fill_index)
+        instructions::settle_fill(ctx, fill_index)
     }
 }
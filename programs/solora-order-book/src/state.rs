@@ -1,7 +1,77 @@
 use anchor_lang::prelude::*;
-use solana_program::pubkey::Pubkey;
+use solana_program::{pubkey, pubkey::Pubkey};
 
-pub const EVENT_SIZE: usize = 8 + 1 + 1 + 4 + 32 + 32 + 1 + 32 + 32 + 4 + (4 + 200);
+pub const EVENT_VERSION: u8 = 1;
+
+pub const EVENT_SIZE: usize =
+    8 + 1 + 1 + 4 + 32 + 32 + 1 + 32 + 32 + 4 + 32 + 1 + 8 + 8 + 1 + 32 + 4 + 8 + 8 + 1 + 1 + 1 + 32 +
+    (4 + 32 * MAX_CURRENCY_MINTS) + (4 + 200);
+
+/// Seconds after close time that an oracle event can be resolved from its feed before the
+/// authority is allowed to settle it instead
+pub const ORACLE_RESOLVE_PERIOD: i64 = 15;
+/// Seconds after close time that the feed price used to resolve an oracle event can be published
+pub const ORACLE_PUBLISH_TOLERANCE: i64 = 10;
+
+/// Pyth oracle programs that own the price feeds oracle events can use, on mainnet and devnet
+#[cfg(not(feature = "localnet"))]
+pub const PYTH_PROGRAM_IDS: &[Pubkey] = &[
+    pubkey!("FsJ3A3u2vn5cTVofAjvy6y5kwABJAqYWpe4975bi2epH"),
+    pubkey!("gSbePebfvPy7tRqimPoVecS2UsBvYv46ynrzWocc92s"),
+];
+
+/// Also allows the mock program used by localnet tests, which anyone could deploy elsewhere
+#[cfg(feature = "localnet")]
+pub const PYTH_PROGRAM_IDS: &[Pubkey] = &[
+    pubkey!("FsJ3A3u2vn5cTVofAjvy6y5kwABJAqYWpe4975bi2epH"),
+    pubkey!("gSbePebfvPy7tRqimPoVecS2UsBvYv46ynrzWocc92s"),
+    pubkey!("Fg6PaFpoGXkYsidMpWTK6W2BeZ7FEfcYkg476zPFsLnS"),
+];
+
+/// Max number of currency mints orders on an event can use
+pub const MAX_CURRENCY_MINTS: usize = 4;
+
+/// Outcome an event is settled with when it's cancelled or can't be resolved. Both sides of
+/// every fill are refunded.
+pub const OUTCOME_VOID: u8 = u8::MAX;
+
+/// How an oracle event compares the feed price at close time to its strike price(s). The event
+/// resolves to outcome 1 when the comparison holds and outcome 2 otherwise.
+#[derive(
+    AnchorSerialize,
+    AnchorDeserialize,
+    Clone,
+    Copy,
+    PartialEq,
+    Debug
+)]
+pub enum Comparison {
+    /// Not an oracle event, the authority settles it
+    None,
+    Above,
+    Below,
+    /// Inclusive of both strike prices
+    Between,
+}
+
+/// Settings an event is created with
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct CreateEventParams {
+    pub fee_account: Pubkey,
+    pub fee_bps: u32,
+    pub close_time: i64,
+    pub metadata_uri: String,
+    pub comparison: Comparison,
+    pub strike_price: u64,
+    pub strike_price_high: u64,
+    pub price_decimals: u8,
+    pub arbiter: Pubkey,
+    pub dispute_period: u32,
+    pub dispute_bond: u64,
+    pub num_outcomes: u8,
+    pub outcome_labels_hash: [u8; 32],
+    pub currency_mints: Vec<Pubkey>,
+}
 
 #[account]
 pub struct Event {
@@ -13,7 +83,7 @@ pub struct Event {
     pub authority: Pubkey,
     /// Bytes generated from sha256 of the event description
     pub id: [u8; 32],
-    /// Outcome of the event or 0 if not yet resolved
+    /// Outcome of the event, 0 if not yet resolved or OUTCOME_VOID if voided
     pub outcome: u8,
     /// Account to receive fees
     pub fee_account: Pubkey,
@@ -21,10 +91,74 @@ pub struct Event {
     pub fee_bps: u32,
     /// Timestamp of when the event is closed to new orders/fills or 0 for never
     pub close_time: i64,
+    /// Pyth price feed used to resolve the event if it has a comparison
+    pub pyth_feed: Pubkey,
+    pub comparison: Comparison,
+    /// Price with price_decimals to compare the feed price against
+    pub strike_price: u64,
+    /// Upper strike price used for the between comparison
+    pub strike_price_high: u64,
+    /// Number of decimals of the strike prices
+    pub price_decimals: u8,
+    /// Account that rules on disputes of the settled outcome
+    pub arbiter: Pubkey,
+    /// Seconds after settlement that the outcome can be disputed or 0 for no disputes
+    pub dispute_period: u32,
+    /// Lamports a disputer must post to challenge the outcome. Only one dispute can be open per
+    /// event, so the bond should be large enough that holding the dispute slot to delay payouts
+    /// until the arbiter deadline costs more than it could gain, e.g. a share of the event volume.
+    pub dispute_bond: u64,
+    /// Timestamp of when the outcome was set
+    pub settle_time: i64,
+    pub is_disputed: bool,
+    /// Set once the outcome can no longer change
+    pub finalized: bool,
+    /// Outcomes are numbered 1 to num_outcomes
+    pub num_outcomes: u8,
+    /// Optional sha256 of the outcome labels so clients can verify them
+    pub outcome_labels_hash: [u8; 32],
+    /// SPL token mints, or the native mint for SOL, that orders are allowed to use
+    pub currency_mints: Vec<Pubkey>,
     pub metadata_uri: String,
 }
 
 impl Event {
+    pub fn is_oracle_event(&self) -> bool {
+        self.comparison != Comparison::None
+    }
+
+    /// Outcome that can be bet on
+    pub fn is_valid_outcome(&self, outcome: u8) -> bool {
+        outcome != 0 && outcome <= self.num_outcomes
+    }
+
+    /// Outcome that the event can be settled with
+    pub fn is_valid_result(&self, outcome: u8) -> bool {
+        self.is_valid_outcome(outcome) || outcome == OUTCOME_VOID
+    }
+
+    pub fn is_allowed_mint(&self, currency_mint: &Pubkey) -> bool {
+        self.currency_mints.contains(currency_mint)
+    }
+
+    /// The outcome is final once the dispute period has passed without a dispute, the arbiter
+    /// has ruled on the dispute or the arbiter missed its deadline
+    pub fn is_finalized(&self, timestamp: i64) -> bool {
+        if self.outcome == 0 {
+            return false;
+        }
+
+        self.finalized ||
+            (!self.is_disputed && timestamp >= self.settle_time + self.dispute_period as i64) ||
+            (self.is_disputed && timestamp >= self.arbiter_deadline())
+    }
+
+    /// The arbiter has another dispute period after the dispute period ends to rule on a dispute,
+    /// after which the settled outcome stands
+    pub fn arbiter_deadline(&self) -> i64 {
+        self.settle_time + 2 * self.dispute_period as i64
+    }
+
     pub fn auth_seeds(&self) -> [&[u8]; 3] {
         [
             b"event".as_ref(),
@@ -46,6 +180,7 @@ pub struct Order {
     pub authority: Pubkey,
     pub event: Pubkey,
     pub outcome: u8,
+    /// Amount backing the remaining ask. The part backing each fill is moved to the fill account.
     pub amount: u64,
     /// SPL token mint or native mint for SOL
     pub currency_mint: Pubkey,
@@ -53,17 +188,26 @@ pub struct Order {
     pub remaining_ask: u64,
     /// Expires any remaining bet_amount after this timestamp or 0 if never expires
     pub expiry: i64,
-    /// Used instead of separate accounts to reduce number of accounts needed when settling
-    pub fills: Vec<Fill>
+    /// Index to use for the next fill of this order
+    pub fill_index: u32,
 }
 
 impl Order {
-    pub fn space(fill_len: usize) -> usize {
-        ORDER_SIZE + (fill_len * FILL_SIZE)
-    }
+    /// Amount actually filled for the given ask after rounding to the order's odds and the part of
+    /// the order amount that backs it
+    pub fn get_fill_amounts(&self, amount: u64) -> (u64, u64) {
+        let order_obligation = (amount as u128)
+            .checked_mul(10_000).unwrap()
+            .checked_div(self.ask_bps as u128).unwrap() as u64;
+        // Ensure the obligation is not too large.
+        let safe_amount = (order_obligation as u128)
+            .checked_mul(self.ask_bps as u128).unwrap()
+            .checked_div(10_000).unwrap() as u64;
+        let order_amount = (safe_amount as u128)
+            .checked_mul(10_000).unwrap()
+            .checked_div(self.ask_bps as u128).unwrap() as u64;
 
-    pub fn get_fill_index(&self, authority: Pubkey) -> Option<usize> {
-        self.fills.iter().position(|fill| fill.authority == authority)
+        (safe_amount, order_amount)
     }
 
     pub fn auth_seeds<'a>(&'a self, index_bytes: &'a [u8]) -> [&'a [u8]; 4] {
@@ -76,13 +220,117 @@ impl Order {
     }
 }
 
-pub const FILL_SIZE: usize = 4 + 32 + 1 + 8 + 1;
+pub const FILL_SIZE: usize = 8 + 1 + 32 + 4 + 32 + 1 + 8 + 8 + 32 + 32 + 1 + 32;
 
-#[derive(AnchorSerialize, AnchorDeserialize, Clone, Default)]
+/// Holds both sides of a fill until it's settled. Everything needed from the order is copied so
+/// fills can be settled without the order, which is closed once it can't be filled anymore.
+#[account]
 pub struct Fill {
+    /// Bump seed used to generate the program address / authority
+    pub bump: [u8; 1],
+    pub order: Pubkey,
+    /// Index of this fill within the order
     pub index: u32,
+    /// Account that filled the order
     pub authority: Pubkey,
     pub outcome: u8,
+    /// Amount paid by the fill authority
     pub amount: u64,
-    pub is_settled: bool
-}
\ No newline at end of file
+    /// Amount moved from the order to back this fill
+    pub order_amount: u64,
+    pub event: Pubkey,
+    pub order_authority: Pubkey,
+    pub order_outcome: u8,
+    /// SPL token mint or native mint for SOL
+    pub currency_mint: Pubkey,
+}
+
+impl Fill {
+    /// Amounts paid to the order authority and the fill authority after fees and the fee for the
+    /// given outcome. A fill is a bet between the order's outcome and the fill's outcome only, so
+    /// any other outcome refunds both sides.
+    pub fn get_payouts(&self, outcome: u8, fee_bps: u32) -> (u64, u64, u64) {
+        let is_order_winner = self.order_outcome == outcome;
+        let is_draw = !is_order_winner && self.outcome != outcome;
+        let total = self.order_amount.checked_add(self.amount).unwrap();
+
+        if is_draw {
+            return (self.order_amount, self.amount, 0);
+        }
+
+        // The winner pays the fee on what they won
+        let winnings = if is_order_winner { self.amount } else { self.order_amount };
+        let fee = (winnings as u128).checked_mul(fee_bps as u128).unwrap()
+            .checked_div(10_000).unwrap() as u64;
+        let payout = total.checked_sub(fee).unwrap();
+
+        if is_order_winner {
+            (payout, 0, fee)
+        } else {
+            (0, payout, fee)
+        }
+    }
+
+    pub fn auth_seeds<'a>(&'a self, index_bytes: &'a [u8]) -> [&'a [u8]; 4] {
+        [
+            b"fill".as_ref(),
+            self.order.as_ref(),
+            index_bytes,
+            self.bump.as_ref()
+        ]
+    }
+}
+
+pub const DISPUTE_SIZE: usize = 8 + 1 + 32 + 32 + 1 + 8;
+
+#[account]
+pub struct Dispute {
+    /// Bump seed used to generate the program address / authority
+    pub bump: [u8; 1],
+    pub event: Pubkey,
+    /// Account that posted the bond
+    pub authority: Pubkey,
+    /// Outcome the disputer claims is correct
+    pub outcome: u8,
+    /// Lamports held by this account that are refunded if the arbiter overturns the outcome
+    pub bond: u64,
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::state::Fill;
+    use solana_program::pubkey::Pubkey;
+
+    /// Fills are settled from their own data alone, as if the order was already closed
+    fn fill(order_outcome: u8, outcome: u8) -> Fill {
+        Fill {
+            bump: [0],
+            order: Pubkey::new_unique(),
+            index: 0,
+            authority: Pubkey::new_unique(),
+            outcome,
+            amount: 50,
+            order_amount: 100,
+            event: Pubkey::new_unique(),
+            order_authority: Pubkey::new_unique(),
+            order_outcome,
+            currency_mint: Pubkey::new_unique(),
+        }
+    }
+
+    #[test]
+    fn fill_payouts_order_winner() {
+        assert_eq!((145, 0, 5), fill(1, 2).get_payouts(1, 1000));
+    }
+
+    #[test]
+    fn fill_payouts_fill_winner() {
+        assert_eq!((0, 140, 10), fill(1, 2).get_payouts(2, 1000));
+    }
+
+    #[test]
+    fn fill_payouts_draw() {
+        assert_eq!((100, 50, 0), fill(1, 2).get_payouts(3, 1000));
+        assert_eq!((100, 50, 0), fill(1, 2).get_payouts(u8::MAX, 1000));
+    }
+}
@@ -7,6 +7,9 @@ use solana_program::program::{invoke, invoke_signed};
 use solana_program::program_pack::{IsInitialized, Pack};
 use spl_associated_token_account::instruction::create_associated_token_account;
 use spl_token::state::Account as SplAccount;
+use solora_pyth_price::change_price_decimals;
+use crate::error::Error;
+use crate::state::Event;
 
 #[error_code]
 pub enum UtilError {
@@ -46,6 +49,25 @@ pub fn transfer_sol<'a>(
     )?)
 }
 
+pub fn transfer_sol_pda(
+    // The program owned account to transfer from.
+    pda: &mut AccountInfo,
+    dst: &mut AccountInfo,
+    amount: u64,
+) -> Result<()> {
+    **pda.try_borrow_mut_lamports()? = pda
+        .lamports()
+        .checked_sub(amount)
+        .ok_or(Error::OverflowError)?;
+
+    **dst.try_borrow_mut_lamports()? = dst
+        .lamports()
+        .checked_add(amount)
+        .ok_or(Error::OverflowError)?;
+
+    Ok(())
+}
+
 /// Transfers SOL or SPL tokens between two accounts. The native mint can be used for the
 /// currency mint to specifically transfer SOL.
 pub fn transfer<'a>(
@@ -157,6 +179,73 @@ pub fn transfer<'a>(
     Ok(())
 }
 
+/// Verifies the currency accounts passed in remaining_accounts are for the order's currency mint
+/// and that the mint is allowed by the event. The currency account must be the ATA of the order or
+/// one of its fills.
+pub fn assert_order_currency(
+    event: &Event,
+    owner: &Pubkey,
+    order_currency_mint: &Pubkey,
+    currency_mint: &AccountInfo,
+    currency_account: &AccountInfo,
+) -> Result<()> {
+    if !event.is_allowed_mint(order_currency_mint) {
+        return err!(Error::CurrencyMintNotAllowed);
+    }
+
+    if currency_mint.key != order_currency_mint {
+        return err!(Error::InvalidCurrencyMint);
+    }
+
+    if *currency_account.key != get_associated_token_address(owner, order_currency_mint) {
+        return err!(Error::InvalidCurrencyAccount);
+    }
+
+    Ok(())
+}
+
+/// Creates a program owned account at a PDA. Also works if the address was already sent lamports.
+pub fn create_pda_account<'a>(
+    payer: &AccountInfo<'a>,
+    pda: &AccountInfo<'a>,
+    system_program: &AccountInfo<'a>,
+    owner: &Pubkey,
+    space: usize,
+    signer_seeds: &[&[u8]],
+) -> Result<()> {
+    let rent = Rent::get()?.minimum_balance(space);
+    let lamports = pda.lamports();
+
+    if lamports == 0 {
+        invoke_signed(
+            &system_instruction::create_account(payer.key, pda.key, rent, space as u64, owner),
+            &[payer.clone(), pda.clone(), system_program.clone()],
+            &[signer_seeds],
+        )?;
+    } else {
+        let top_up = rent.saturating_sub(lamports);
+        if top_up > 0 {
+            invoke(
+                &system_instruction::transfer(payer.key, pda.key, top_up),
+                &[payer.clone(), pda.clone(), system_program.clone()],
+            )?;
+        }
+
+        invoke_signed(
+            &system_instruction::allocate(pda.key, space as u64),
+            &[pda.clone(), system_program.clone()],
+            &[signer_seeds],
+        )?;
+        invoke_signed(
+            &system_instruction::assign(pda.key, owner),
+            &[pda.clone(), system_program.clone()],
+            &[signer_seeds],
+        )?;
+    }
+
+    Ok(())
+}
+
 pub fn is_native_mint(key: Pubkey) -> bool {
     return key == spl_token::native_mint::ID;
 }
@@ -242,4 +331,12 @@ pub fn make_ata<'a>(
     )?;
 
     Ok(())
+}
+
+/// Converts a price with exponent `pyth_expo` to `target_decimals` decimals. Decimals past
+/// `target_decimals` are rounded down. Shares the price game's conversion so both programs read
+/// pyth prices the same way.
+pub fn get_price_with_decimal_change(pyth_price: i64, pyth_expo: i32, target_decimals: u8) -> Result<u64> {
+    change_price_decimals(pyth_price, pyth_expo, target_decimals)
+        .ok_or_else(|| error!(Error::InvalidPrice))
 }
\ No newline at end of file
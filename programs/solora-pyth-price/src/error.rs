@@ -61,4 +61,52 @@ pub enum Error {
     InvalidFee,
     #[msg("Invalid fee burn")]
     InvalidFeeBurn,
+
+    /// 25
+    #[msg("Events for this config are still in progress")]
+    EventsInProgress,
+    #[msg("The outcome does not match the existing order")]
+    OutcomeMismatch,
+    #[msg("Invalid crank reward")]
+    InvalidCrankReward,
+    #[msg("Invalid order accounts")]
+    InvalidOrderAccounts,
+    #[msg("The claim period for this event has expired")]
+    ClaimPeriodExpired,
+
+    /// 30
+    #[msg("The claim period for this event has not expired")]
+    ClaimPeriodNotExpired,
+    #[msg("Invalid fee recipients")]
+    InvalidFeeRecipients,
+    #[msg("The fee recipient account does not match the event")]
+    InvalidFeeRecipient,
+    #[msg("Invalid referral fee")]
+    InvalidReferralFee,
+    #[msg("Invalid referrer")]
+    InvalidReferrer,
+
+    /// 35
+    #[msg("The referrer account does not match the order")]
+    InvalidReferrerAccount,
+    #[msg("The fee burn account does not match the event")]
+    InvalidFeeBurnAccount,
+    #[msg("Invalid max confidence")]
+    InvalidMaxConfidence,
+    #[msg("Invalid grace period")]
+    InvalidGracePeriod,
+    #[msg("Invalid max price age")]
+    InvalidMaxPriceAge,
+
+    /// 40
+    #[msg("Invalid publish time tolerance")]
+    InvalidPublishTimeTolerance,
+    #[msg("Invalid oracle account")]
+    InvalidOracle,
+    #[msg("The price update is for a different feed")]
+    FeedIdMismatch,
+    #[msg("Invalid event config account")]
+    InvalidEventConfig,
+    #[msg("Invalid create thread")]
+    InvalidCreateThread,
 }
\ No newline at end of file
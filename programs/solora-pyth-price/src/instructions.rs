@@ -1,19 +1,37 @@
 mod create_event_config;
 mod update_event_config;
+mod migrate_event_config;
+mod deposit_vault;
 mod create_event;
+mod create_next_event;
 mod create_order;
+mod increase_order;
+mod create_referrer;
 mod set_lock_price;
 mod settle_order;
+mod settle_orders;
 mod settle_event;
 mod settle_expired_event;
 mod close_accounts;
+mod sweep_event;
+mod close_expired_order;
+mod close_event_config;
 
 pub use create_event_config::*;
 pub use update_event_config::*;
+pub use migrate_event_config::*;
+pub use deposit_vault::*;
 pub use create_event::*;
+pub use create_next_event::*;
 pub use create_order::*;
+pub use increase_order::*;
+pub use create_referrer::*;
 pub use set_lock_price::*;
 pub use settle_order::*;
+pub use settle_orders::*;
 pub use settle_event::*;
 pub use settle_expired_event::*;
-pub use close_accounts::*;
\ No newline at end of file
+pub use close_accounts::*;
+pub use sweep_event::*;
+pub use close_expired_order::*;
+pub use close_event_config::*;
\ No newline at end of file
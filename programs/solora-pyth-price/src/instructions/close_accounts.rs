@@ -96,10 +96,16 @@ pub fn close_accounts<'info>(ctx: Context<'_, '_, '_, 'info, CloseAccounts<'info
         ))?;
     }
 
-    // Close the event if all orders have been settled
+    // Close the event once all orders have been settled. Swept events are kept until orders that
+    // were never settled are closed with `close_expired_order`, so late settles get a clear error.
     if event.up_count + event.down_count == event.orders_settled {
-        // Empty/close the currency account as well if not using native mint
-        if !is_native_mint(ctx.accounts.event_config.currency_mint) {
+        let residual_amount;
+
+        // Empty/close the currency account as well if not using native mint. Sweeping already
+        // emptied and closed it.
+        if event.claim_expired {
+            residual_amount = 0;
+        } else if !is_native_mint(ctx.accounts.event_config.currency_mint) {
             let remaining_accounts = &mut ctx.remaining_accounts.iter();
             let currency_mint = next_account_info(remaining_accounts)?;
             let event_currency_account = next_account_info(remaining_accounts)?;
@@ -108,6 +114,7 @@ pub fn close_accounts<'info>(ctx: Context<'_, '_, '_, 'info, CloseAccounts<'info
 
             let token_account =
                 Account::<'info, TokenAccount>::try_from(event_currency_account)?;
+            residual_amount = token_account.amount;
             if token_account.amount > 0 {
                 let ata_program = next_account_info(remaining_accounts)?;
                 let rent = next_account_info(remaining_accounts)?;
@@ -143,10 +150,32 @@ pub fn close_accounts<'info>(ctx: Context<'_, '_, '_, 'info, CloseAccounts<'info
                     &[&auth_seeds]
                 )
             )?;
+        } else {
+            // Anything above rent is left over from rounding or fees
+            let event_info = event.to_account_info();
+            let rent = Rent::get()?.minimum_balance(event_info.data_len());
+            residual_amount = event_info.lamports().saturating_sub(rent);
         }
 
+        emit!(EventClosed {
+            event_config: ctx.accounts.event_config.key(),
+            event: event.key(),
+            authority: ctx.accounts.authority.key(),
+            residual_amount,
+        });
+
         event.close(ctx.accounts.authority.to_account_info())?;
     }
 
     Ok(())
 }
+
+#[event]
+pub struct EventClosed {
+    pub event_config: Pubkey,
+    pub event: Pubkey,
+    /// Account the residual and rent were sent to
+    pub authority: Pubkey,
+    /// Currency left in the event after all orders were settled
+    pub residual_amount: u64,
+}
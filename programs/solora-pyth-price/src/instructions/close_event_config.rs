@@ -0,0 +1,144 @@
+use crate::error::Error;
+use crate::state::EventConfig;
+use anchor_lang::prelude::*;
+use anchor_spl::token;
+use anchor_spl::token::{CloseAccount, TokenAccount};
+use clockwork_sdk::{
+    ID as thread_program_ID,
+    cpi::{
+        thread_delete,
+        ThreadDelete,
+    },
+    ThreadProgram,
+};
+use crate::util::{is_native_mint, transfer, transfer_sol};
+
+#[derive(Accounts)]
+pub struct CloseEventConfig<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    #[account(
+        mut,
+        close = authority,
+        seeds = [
+            b"event_config".as_ref(),
+            event_config.authority.as_ref(),
+            event_config.pyth_feed.as_ref(),
+            event_config.currency_mint.as_ref()
+        ],
+        bump = event_config.bump[0],
+        has_one = authority,
+        constraint = event_config.is_create_thread(&create_thread.key()) @ Error::InvalidCreateThread
+    )]
+    pub event_config: Box<Account<'info, EventConfig>>,
+
+    /// CHECK: Safe due to event config constraint. Configs without a create thread can pass any
+    /// writable account, e.g. the authority, which is left untouched.
+    #[account(mut)]
+    pub create_thread: UncheckedAccount<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"vault".as_ref(), event_config.key().as_ref()],
+        bump
+    )]
+    pub vault: SystemAccount<'info>,
+
+    #[account(address = thread_program_ID)]
+    pub clockwork: Program<'info, ThreadProgram>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/// Stops event creation and returns the vault balance to the authority. Events that were already
+/// created still need the config to be settled and closed, so only close it after they are gone.
+pub fn close_event_config<'info>(ctx: Context<'_, '_, '_, 'info, CloseEventConfig<'info>>) -> Result<()> {
+    let event_config = &ctx.accounts.event_config;
+
+    // The last event created locks at next_event_start and settles an interval later
+    let timestamp = Clock::get()?.unix_timestamp;
+    if event_config.next_event_start + event_config.interval_seconds as i64 > timestamp {
+        return err!(Error::EventsInProgress);
+    }
+
+    if event_config.has_create_thread() && !ctx.accounts.create_thread.data_is_empty() {
+        let auth_seeds = event_config.auth_seeds();
+        thread_delete(CpiContext::new_with_signer(
+            ctx.accounts.clockwork.to_account_info(),
+            ThreadDelete {
+                authority: event_config.to_account_info(),
+                close_to: ctx.accounts.authority.to_account_info(),
+                thread: ctx.accounts.create_thread.to_account_info(),
+            },
+            &[&auth_seeds]
+        ))?;
+    }
+
+    let event_config_key = event_config.key();
+    let vault_bump = [*ctx.bumps.get("vault").unwrap()];
+    let vault_seeds: &[&[u8]] = &[b"vault".as_ref(), event_config_key.as_ref(), &vault_bump];
+
+    // Empty/close the vault currency account as well if not using native mint
+    if !is_native_mint(event_config.currency_mint) {
+        let remaining_accounts = &mut ctx.remaining_accounts.iter();
+        let currency_mint = next_account_info(remaining_accounts)?;
+        let vault_currency_account = next_account_info(remaining_accounts)?;
+        let authority_currency_account = next_account_info(remaining_accounts)?;
+        let token_program = next_account_info(remaining_accounts)?;
+
+        if event_config.currency_mint != currency_mint.key() {
+            return err!(Error::InvalidMint);
+        }
+
+        if !vault_currency_account.data_is_empty() {
+            let token_account =
+                Account::<'info, TokenAccount>::try_from(vault_currency_account)?;
+            if token_account.amount > 0 {
+                let ata_program = next_account_info(remaining_accounts)?;
+                let rent = next_account_info(remaining_accounts)?;
+
+                transfer(
+                    &ctx.accounts.vault.to_account_info(),
+                    &ctx.accounts.authority.to_account_info(),
+                    vault_currency_account.into(),
+                    authority_currency_account.into(),
+                    currency_mint.into(),
+                    Option::from(&ctx.accounts.authority.to_account_info()),
+                    ata_program.into(),
+                    token_program.into(),
+                    &ctx.accounts.system_program.to_account_info(),
+                    rent.into(),
+                    Some(vault_seeds),
+                    None,
+                    token_account.amount
+                )?;
+            }
+
+            token::close_account(
+                CpiContext::new_with_signer(
+                    token_program.to_account_info(),
+                    CloseAccount {
+                        account: vault_currency_account.to_account_info(),
+                        destination: ctx.accounts.authority.to_account_info(),
+                        authority: ctx.accounts.vault.to_account_info(),
+                    },
+                    &[vault_seeds]
+                )
+            )?;
+        }
+    }
+
+    let vault_lamports = ctx.accounts.vault.lamports();
+    if vault_lamports > 0 {
+        transfer_sol(
+            &ctx.accounts.vault.to_account_info(),
+            &ctx.accounts.authority.to_account_info(),
+            &ctx.accounts.system_program.to_account_info(),
+            Some(vault_seeds),
+            vault_lamports,
+        )?;
+    }
+
+    Ok(())
+}
@@ -0,0 +1,58 @@
+use anchor_lang::prelude::*;
+use crate::state::{Event, Order, Outcome};
+use crate::error::Error;
+
+#[derive(Accounts)]
+pub struct CloseExpiredOrder<'info> {
+    /// CHECK: Safe due to order constraint
+    #[account(mut)]
+    pub authority: UncheckedAccount<'info>,
+
+    #[account(
+        mut,
+        constraint = event.claim_expired @ Error::ClaimPeriodNotExpired,
+    )]
+    pub event: Box<Account<'info, Event>>,
+
+    #[account(
+        mut,
+        close = authority,
+        has_one = event,
+        has_one = authority
+    )]
+    pub order: Box<Account<'info, Order>>,
+}
+
+/// Closes an order that was never settled before its event was swept, returning the order's rent
+/// to its authority. Nothing is paid out since the event's funds went to the fee account, which is
+/// why `settle_order` fails with `ClaimPeriodExpired` for these orders. Anyone can call this so the
+/// config authority can close the remaining orders and then the event with `close_accounts`.
+pub fn close_expired_order<'info>(
+    ctx: Context<'_, '_, '_, 'info, CloseExpiredOrder<'info>>,
+) -> Result<()> {
+    // Counted as settled so the event can be closed once every order is gone
+    let event = &mut ctx.accounts.event;
+    event.orders_settled = event.orders_settled.checked_add(1)
+        .ok_or(Error::OverflowError)?;
+
+    let order = &ctx.accounts.order;
+    emit!(OrderExpired {
+        event: order.event,
+        order: order.key(),
+        authority: order.authority,
+        outcome: order.outcome,
+        amount: order.amount,
+    });
+
+    Ok(())
+}
+
+#[event]
+pub struct OrderExpired {
+    pub event: Pubkey,
+    pub order: Pubkey,
+    pub authority: Pubkey,
+    pub outcome: Outcome,
+    /// Amount of the order that was swept instead of settled
+    pub amount: u64,
+}
@@ -10,12 +10,25 @@ use clockwork_sdk::{
     state::{Trigger, Thread, ThreadSettings},
     ThreadProgram,
 };
-use pyth_sdk_solana::{load_price_feed_from_account_info, Price};
 use solana_program::instruction::Instruction;
-use crate::state::{Event, EVENT_SIZE, EVENT_VERSION, EventConfig, MAX_PRICE_DECIMALS, Outcome};
+use crate::state::{
+    Event,
+    EVENT_SIZE,
+    EVENT_VERSION,
+    EventConfig,
+    FeeRecipient,
+    MAX_PRICE_DECIMALS,
+    OracleKind,
+    Outcome,
+};
 use crate::error::Error;
+use crate::oracle::load_oracle;
+use crate::instructions::update_create_thread;
 use crate::util::{is_native_mint, transfer, transfer_sol};
 
+/// Lamports paid to clockwork per thread execution. Higher than default fee to prioritize.
+pub const THREAD_FEE: u64 = 10_000;
+
 #[derive(Accounts)]
 pub struct CreateEvent<'info> {
     /// CHECK: Allow any account to be the settle authority
@@ -33,7 +46,8 @@ pub struct CreateEvent<'info> {
         bump = event_config.bump[0],
         has_one = authority,
         has_one = pyth_feed,
-        has_one = currency_mint
+        has_one = currency_mint,
+        constraint = event_config.is_create_thread(&create_thread.key()) @ Error::InvalidCreateThread
     )]
     pub event_config: Box<Account<'info, EventConfig>>,
 
@@ -72,18 +86,128 @@ pub struct CreateEvent<'info> {
     )]
     pub settle_thread: SystemAccount<'info>,
 
+    /// CHECK: Safe due to event config constraint. Configs without a create thread can pass any
+    /// writable account, e.g. the authority, which is left untouched.
+    #[account(mut)]
+    pub create_thread: UncheckedAccount<'info>,
+
     #[account(address = thread_program_ID)]
     pub clockwork: Program<'info, ThreadProgram>,
     pub system_program: Program<'info, System>,
     pub rent: Sysvar<'info, Rent>,
 }
 
+/// Creates the next event for a config manually. Also takes the config's create thread, which is
+/// rescheduled after the new event.
 pub fn create_event<'info>(
     ctx: Context<'_, '_, '_, 'info, CreateEvent<'info>>,
     fee_bps: u32,
     initial_liquidity: u64,
     fee_burn_bps: u32,
 ) -> Result<()> {
+    let event_clone = ctx.accounts.event.to_account_info().clone();
+
+    start_event(
+        &mut ctx.accounts.event_config,
+        &mut ctx.accounts.event,
+        *ctx.bumps.get("event").unwrap(),
+        StartEventAccounts {
+            payer: &ctx.accounts.authority.to_account_info(),
+            pyth_feed: &ctx.accounts.pyth_feed.to_account_info(),
+            fee_account: &ctx.accounts.fee_account.to_account_info(),
+            lock_thread: &ctx.accounts.lock_thread.to_account_info(),
+            settle_thread: &ctx.accounts.settle_thread.to_account_info(),
+            clockwork: &ctx.accounts.clockwork.to_account_info(),
+            system_program: &ctx.accounts.system_program.to_account_info(),
+        },
+        fee_bps,
+        fee_burn_bps,
+        initial_liquidity,
+    )?;
+
+    let event_config = &ctx.accounts.event_config;
+    if initial_liquidity > 0 {
+        if is_native_mint(event_config.currency_mint) {
+            transfer_sol(
+                &ctx.accounts.authority.to_account_info(),
+                &event_clone,
+                &ctx.accounts.system_program.to_account_info(),
+                None,
+                initial_liquidity,
+            )?;
+        } else {
+            let remaining_accounts = &mut ctx.remaining_accounts.iter();
+            let event_currency_account = next_account_info(remaining_accounts)?;
+            let authority_currency_account = next_account_info(remaining_accounts)?;
+            let token_program = next_account_info(remaining_accounts)?;
+            let ata_program = next_account_info(remaining_accounts)?;
+
+            if event_config.currency_mint != ctx.accounts.currency_mint.key() {
+                return err!(Error::InvalidMint);
+            }
+
+            transfer(
+                &ctx.accounts.authority.to_account_info(),
+                &event_clone,
+                authority_currency_account.into(),
+                event_currency_account.into(),
+                Option::from(&ctx.accounts.currency_mint.to_account_info()),
+                Option::from(&ctx.accounts.authority.to_account_info()),
+                ata_program.into(),
+                token_program.into(),
+                &ctx.accounts.system_program.to_account_info(),
+                Option::from(&ctx.accounts.rent.to_account_info()),
+                None,
+                None,
+                initial_liquidity,
+            )?;
+        }
+    }
+
+    // Point the create thread at the new next event so it doesn't try to create this one again
+    update_create_thread(
+        event_config,
+        &ctx.accounts.authority.to_account_info(),
+        &ctx.accounts.create_thread.to_account_info(),
+        &ctx.accounts.clockwork.to_account_info(),
+        &ctx.accounts.system_program.to_account_info(),
+        false,
+    )
+}
+
+/// Accounts needed to start an event besides the config and the event itself
+pub struct StartEventAccounts<'a, 'info> {
+    /// Pays for the lock and settle threads
+    pub payer: &'a AccountInfo<'info>,
+    pub pyth_feed: &'a AccountInfo<'info>,
+    pub fee_account: &'a AccountInfo<'info>,
+    pub lock_thread: &'a AccountInfo<'info>,
+    pub settle_thread: &'a AccountInfo<'info>,
+    pub clockwork: &'a AccountInfo<'info>,
+    pub system_program: &'a AccountInfo<'info>,
+}
+
+/// Initializes a new event at the config's next start time and creates the clockwork threads that
+/// will lock and settle it. The caller is responsible for moving `initial_liquidity` into the event.
+pub fn start_event<'info>(
+    event_config: &mut Account<'info, EventConfig>,
+    event: &mut Account<'info, Event>,
+    bump: u8,
+    accounts: StartEventAccounts<'_, 'info>,
+    fee_bps: u32,
+    fee_burn_bps: u32,
+    initial_liquidity: u64,
+) -> Result<()> {
+    let StartEventAccounts {
+        payer,
+        pyth_feed,
+        fee_account,
+        lock_thread,
+        settle_thread,
+        clockwork,
+        system_program,
+    } = accounts;
+
     if fee_bps > 10_000 {
         return err!(Error::InvalidFee)
     }
@@ -93,13 +217,6 @@ pub fn create_event<'info>(
     }
 
     let timestamp = Clock::get()?.unix_timestamp;
-    let authority = &ctx.accounts.authority;
-    let lock_thread = &ctx.accounts.lock_thread;
-    let settle_thread = &ctx.accounts.settle_thread;
-    let system_program = &ctx.accounts.system_program;
-    let clockwork = &ctx.accounts.clockwork;
-
-    let event_config = &mut ctx.accounts.event_config;
     let current_event_start = event_config.next_event_start;
     let lock_time = current_event_start + event_config.interval_seconds as i64;
     if lock_time < timestamp {
@@ -110,24 +227,40 @@ pub fn create_event<'info>(
     event_config.next_event_start = lock_time;
     msg!("event start: {}, lock: {}, settle: {}", current_event_start, lock_time, event_config.next_event_start);
 
-    let price_feed = load_price_feed_from_account_info(&ctx.accounts.pyth_feed.to_account_info()).unwrap();
-    let price: Price = price_feed.get_price_unchecked();
+    let price = load_oracle(event_config.oracle_kind, &event_config.feed_id, pyth_feed)?.latest_price();
     msg!("price.expo: {}", price.expo);
-    let pyth_feed_decimals = (price.expo * -1) as u8;
+    // Switchboard rounds change scale so those events always use the max decimals
+    let pyth_feed_decimals = if event_config.oracle_kind == OracleKind::Switchboard {
+        MAX_PRICE_DECIMALS
+    } else {
+        (-price.expo) as u8
+    };
 
-    let event_clone = ctx.accounts.event.to_account_info().clone();
-    let event = &mut ctx.accounts.event;
-    event.bump = [*ctx.bumps.get("event").unwrap()];
+    event.bump = [bump];
     event.version = EVENT_VERSION;
     event.event_config = event_config.key();
-    event.lock_thread = ctx.accounts.lock_thread.key();
-    event.settle_thread = ctx.accounts.settle_thread.key();
-    event.fee_account = ctx.accounts.fee_account.key();
+    event.lock_thread = lock_thread.key();
+    event.settle_thread = settle_thread.key();
+    event.fee_account = fee_account.key();
     event.fee_bps = fee_bps;
     event.fee_burn_bps = fee_burn_bps;
     event.start_time = current_event_start;
     event.lock_time = lock_time;
     event.wait_period = wait_period;
+    event.claim_period = event_config.claim_period_seconds;
+    event.fee_recipients = event_config.fee_recipients.clone();
+    event.referral_fee_bps = event_config.referral_fee_bps;
+    // Configs from before native burns have no fee burn account, their events keep the whole fee
+    event.fee_burn_account = if is_native_mint(event_config.currency_mint) {
+        event_config.fee_burn_account
+    } else {
+        Pubkey::default()
+    };
+    event.max_conf_bps = event_config.max_conf_bps;
+    event.same_band = event_config.same_band;
+    event.grace_period = event_config.grace_period_seconds;
+    event.max_price_age = event_config.max_price_age_seconds;
+    event.publish_time_tolerance = event_config.publish_time_tolerance_seconds;
     event.outcome = Outcome::Undrawn;
     // Max 4 decimals to consider
     event.price_decimals = if pyth_feed_decimals > MAX_PRICE_DECIMALS {
@@ -140,42 +273,6 @@ pub fn create_event<'info>(
         let half_liquidity = initial_liquidity / 2;
         event.up_amount = half_liquidity as u128;
         event.down_amount = half_liquidity as u128;
-
-        if is_native_mint(event_config.currency_mint) {
-            transfer_sol(
-                &ctx.accounts.authority.to_account_info(),
-                &event.to_account_info(),
-                &ctx.accounts.system_program.to_account_info(),
-                None,
-                initial_liquidity,
-            )?;
-        } else {
-            let remaining_accounts = &mut ctx.remaining_accounts.iter();
-            let event_currency_account = next_account_info(remaining_accounts)?;
-            let authority_currency_account = next_account_info(remaining_accounts)?;
-            let token_program = next_account_info(remaining_accounts)?;
-            let ata_program = next_account_info(remaining_accounts)?;
-
-            if event_config.currency_mint != ctx.accounts.currency_mint.key() {
-                return err!(Error::InvalidMint);
-            }
-
-            transfer(
-                &ctx.accounts.authority.to_account_info(),
-                &event_clone,
-                authority_currency_account.into(),
-                event_currency_account.into(),
-                Option::from(&ctx.accounts.currency_mint.to_account_info()),
-                Option::from(&ctx.accounts.authority.to_account_info()),
-                ata_program.into(),
-                token_program.into(),
-                &ctx.accounts.system_program.to_account_info(),
-                Option::from(&ctx.accounts.rent.to_account_info()),
-                None,
-                None,
-                initial_liquidity,
-            )?;
-        }
     }
 
     // build set_lock_price ix
@@ -204,7 +301,7 @@ pub fn create_event<'info>(
             clockwork.to_account_info(),
             ThreadCreate {
                 authority: event.to_account_info(),
-                payer: authority.to_account_info(),
+                payer: payer.to_account_info(),
                 thread: lock_thread.to_account_info(),
                 system_program: system_program.to_account_info(),
             },
@@ -218,8 +315,6 @@ pub fn create_event<'info>(
         },
     )?;
 
-    // Higher than default fee to prioritize
-    let thread_fee = 10_000u64;
     // set the rate limit of the thread to crank 1 time per slot
     thread_update(
         CpiContext::new_with_signer(
@@ -232,7 +327,7 @@ pub fn create_event<'info>(
             &[&auth_seeds],
         ),
         ThreadSettings {
-            fee: Some(thread_fee),
+            fee: Some(THREAD_FEE),
             kickoff_instruction: None,
             rate_limit: Some(1),
             trigger: None,
@@ -265,7 +360,7 @@ pub fn create_event<'info>(
             clockwork.to_account_info(),
             ThreadCreate {
                 authority: event.to_account_info(),
-                payer: authority.to_account_info(),
+                payer: payer.to_account_info(),
                 thread: settle_thread.to_account_info(),
                 system_program: system_program.to_account_info(),
             },
@@ -291,7 +386,7 @@ pub fn create_event<'info>(
             &[&auth_seeds],
         ),
         ThreadSettings {
-            fee: Some(thread_fee),
+            fee: Some(THREAD_FEE),
             kickoff_instruction: None,
             rate_limit: Some(1),
             trigger: None,
@@ -299,36 +394,20 @@ pub fn create_event<'info>(
     )?;
 
     // Transfer the thread fees to the threads
-    transfer(
-        &authority.to_account_info(),
-        &lock_thread.to_account_info(),
-        None,
-        None,
-        None,
-        None,
+    transfer_sol(
+        payer,
+        lock_thread,
+        system_program,
         None,
-        None,
-        &ctx.accounts.system_program.to_account_info(),
-        None,
-        None,
-        None,
-        thread_fee
+        THREAD_FEE
     )?;
 
-    transfer(
-        &authority.to_account_info(),
-        &settle_thread.to_account_info(),
-        None,
-        None,
-        None,
-        None,
-        None,
-        None,
-        &ctx.accounts.system_program.to_account_info(),
-        None,
-        None,
+    transfer_sol(
+        payer,
+        settle_thread,
+        system_program,
         None,
-        thread_fee
+        THREAD_FEE
     )?;
 
     emit!(EventCreated {
@@ -339,7 +418,9 @@ pub fn create_event<'info>(
         price_decimals: event.price_decimals,
         fee_bps,
         fee_burn_bps,
+        fee_burn_account: event.fee_burn_account,
         fee_account: event.fee_account,
+        fee_recipients: event.fee_recipients.clone(),
         start_time: event.start_time,
         lock_time,
         wait_period,
@@ -359,7 +440,11 @@ pub struct EventCreated {
     pub price_decimals: u8,
     pub fee_bps: u32,
     pub fee_burn_bps: u32,
+    /// Account receiving burned native fees, the default pubkey when fees are burned through the
+    /// currency mint
+    pub fee_burn_account: Pubkey,
     pub fee_account: Pubkey,
+    pub fee_recipients: Vec<FeeRecipient>,
     pub start_time: i64,
     pub lock_time: i64,
     pub wait_period: u32,
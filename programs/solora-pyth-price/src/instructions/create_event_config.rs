@@ -1,6 +1,19 @@
 use anchor_lang::prelude::*;
 use anchor_spl::token::Mint;
-use crate::state::{EventConfig, EVENT_CONFIG_SIZE, EVENT_CONFIG_VERSION};
+use clockwork_sdk::{
+    ID as thread_program_ID,
+    cpi::{
+        thread_create, thread_update,
+        ThreadCreate, ThreadUpdate,
+    },
+    state::{Trigger, Thread, ThreadSettings},
+    ThreadProgram,
+};
+use crate::state::{EventConfig, EventConfigParams, EVENT_CONFIG_SIZE, EVENT_CONFIG_VERSION, OracleKind};
+use crate::error::Error;
+use crate::oracle::load_oracle;
+use crate::instructions::{create_next_event_ix, THREAD_FEE};
+use crate::util::{get_interval_schedule, transfer_sol};
 
 #[derive(Accounts)]
 pub struct CreateEventConfig<'info> {
@@ -22,29 +35,106 @@ pub struct CreateEventConfig<'info> {
     )]
     pub event_config: Box<Account<'info, EventConfig>>,
 
-    /// CHECK: Should be a valid pyth feed
+    /// CHECK: Should be a valid pyth feed or price update account for the oracle kind. Pull oracle
+    /// configs are pinned to this account so it should be a sponsored feed account at a fixed
+    /// address, otherwise the update used for a round could be picked by whoever cranks it.
     #[account()]
     pub pyth_feed: UncheckedAccount<'info>,
 
     pub currency_mint: Account<'info, Mint>,
 
+    /// CHECK: Allow any account to be the fee account
+    #[account()]
+    pub fee_account: UncheckedAccount<'info>,
+
+    #[account(
+        mut,
+        address = Thread::pubkey(event_config.key(), "event_create".into())
+    )]
+    pub create_thread: SystemAccount<'info>,
+
+    #[account(address = thread_program_ID)]
+    pub clockwork: Program<'info, ThreadProgram>,
     pub system_program: Program<'info, System>,
     pub rent: Sysvar<'info, Rent>,
 }
 
+/// Creates a config and the clockwork thread that creates its events every interval. The thread is
+/// funded from the `["vault", event_config]` PDA, see `deposit_vault`. Intervals have to evenly
+/// divide a minute, an hour or a day to be scheduled, anything else fails with `InvalidInterval`.
 pub fn create_event_config<'info>(
     ctx: Context<'_, '_, '_, 'info, CreateEventConfig<'info>>,
-    interval_seconds: u32,
-    next_event_start: i64
+    params: EventConfigParams,
+    oracle_kind: OracleKind,
+    feed_id: [u8; 32],
 ) -> Result<()> {
+    // Make sure prices can be read from the oracle account
+    load_oracle(oracle_kind, &feed_id, &ctx.accounts.pyth_feed.to_account_info())?;
+
+    let schedule = get_interval_schedule(params.next_event_start, params.interval_seconds)
+        .ok_or(Error::InvalidInterval)?;
+
     let event_config = &mut ctx.accounts.event_config;
     event_config.bump = [*ctx.bumps.get("event_config").unwrap()];
     event_config.version = EVENT_CONFIG_VERSION;
     event_config.authority = ctx.accounts.authority.key();
     event_config.pyth_feed = ctx.accounts.pyth_feed.key();
     event_config.currency_mint = ctx.accounts.currency_mint.key();
-    event_config.interval_seconds = interval_seconds;
-    event_config.next_event_start = next_event_start;
+    event_config.create_thread = ctx.accounts.create_thread.key();
+    event_config.fee_account = ctx.accounts.fee_account.key();
+    event_config.oracle_kind = oracle_kind;
+    event_config.feed_id = feed_id;
+    event_config.set_params(params);
+    event_config.validate()?;
+
+    let auth_seeds = event_config.auth_seeds();
+
+    // Create the thread that will keep creating events every interval
+    thread_create(
+        CpiContext::new_with_signer(
+            ctx.accounts.clockwork.to_account_info(),
+            ThreadCreate {
+                authority: event_config.to_account_info(),
+                payer: ctx.accounts.authority.to_account_info(),
+                thread: ctx.accounts.create_thread.to_account_info(),
+                system_program: ctx.accounts.system_program.to_account_info(),
+            },
+            &[&auth_seeds],
+        ),
+        "event_create".into(),
+        create_next_event_ix(event_config).into(),
+        Trigger::Cron {
+            schedule,
+            skippable: true,
+        },
+    )?;
+
+    thread_update(
+        CpiContext::new_with_signer(
+            ctx.accounts.clockwork.to_account_info(),
+            ThreadUpdate {
+                authority: event_config.to_account_info(),
+                thread: ctx.accounts.create_thread.to_account_info(),
+                system_program: ctx.accounts.system_program.to_account_info(),
+            },
+            &[&auth_seeds],
+        ),
+        ThreadSettings {
+            fee: Some(THREAD_FEE),
+            kickoff_instruction: None,
+            rate_limit: Some(1),
+            trigger: None,
+        },
+    )?;
+
+    // Fund the first run, later runs are funded by the vault
+    transfer_sol(
+        &ctx.accounts.authority.to_account_info(),
+        &ctx.accounts.create_thread.to_account_info(),
+        &ctx.accounts.system_program.to_account_info(),
+        None,
+        THREAD_FEE,
+    )?;
 
     Ok(())
-}
\ No newline at end of file
+}
@@ -0,0 +1,323 @@
+use anchor_lang::prelude::*;
+use anchor_spl::associated_token::get_associated_token_address;
+use anchor_spl::token::Mint;
+use clockwork_sdk::{
+    ID as thread_program_ID,
+    cpi::{
+        thread_update,
+        ThreadUpdate,
+    },
+    state::{Trigger, Thread, ThreadResponse, ThreadSettings},
+    utils::PAYER_PUBKEY,
+    ThreadProgram,
+};
+use solana_program::instruction::Instruction;
+use crate::state::{Event, EVENT_SIZE, EventConfig};
+use crate::error::Error;
+use crate::instructions::{start_event, StartEventAccounts, THREAD_FEE};
+use crate::util::{get_interval_schedule, is_native_mint, transfer, transfer_sol};
+
+#[derive(Accounts)]
+pub struct CreateNextEvent<'info> {
+    /// Pays for the new accounts and is reimbursed by the vault. Clockwork injects its worker here.
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [
+            b"event_config".as_ref(),
+            event_config.authority.as_ref(),
+            event_config.pyth_feed.as_ref(),
+            event_config.currency_mint.as_ref()
+        ],
+        bump = event_config.bump[0],
+        has_one = pyth_feed,
+        has_one = currency_mint,
+        has_one = fee_account,
+        has_one = create_thread
+    )]
+    pub event_config: Box<Account<'info, EventConfig>>,
+
+    #[account(
+        init,
+        seeds = [
+            b"event".as_ref(),
+            event_config.key().as_ref(),
+            &event_config.next_event_start.to_le_bytes()
+        ],
+        bump,
+        space = EVENT_SIZE,
+        payer = payer,
+    )]
+    pub event: Box<Account<'info, Event>>,
+
+    /// Holds the lamports (and currency for SPL configs) used to fund events created by the thread
+    #[account(
+        mut,
+        seeds = [b"vault".as_ref(), event_config.key().as_ref()],
+        bump
+    )]
+    pub vault: SystemAccount<'info>,
+
+    /// CHECK: Safe due to event config constraint
+    #[account(mut)]
+    pub create_thread: UncheckedAccount<'info>,
+
+    /// CHECK: Safe due to event config constraint
+    #[account()]
+    pub pyth_feed: UncheckedAccount<'info>,
+
+    /// CHECK: Safe due to event config constraint
+    #[account()]
+    pub fee_account: UncheckedAccount<'info>,
+
+    pub currency_mint: Account<'info, Mint>,
+
+    #[account(
+        mut,
+        address = Thread::pubkey(event.key(), "event_lock".into())
+    )]
+    pub lock_thread: SystemAccount<'info>,
+
+    #[account(
+        mut,
+        address = Thread::pubkey(event.key(), "event_settle".into())
+    )]
+    pub settle_thread: SystemAccount<'info>,
+
+    #[account(address = thread_program_ID)]
+    pub clockwork: Program<'info, ThreadProgram>,
+    pub system_program: Program<'info, System>,
+    pub rent: Sysvar<'info, Rent>,
+}
+
+pub fn create_next_event<'info>(
+    ctx: Context<'_, '_, '_, 'info, CreateNextEvent<'info>>,
+) -> Result<ThreadResponse> {
+    let timestamp = Clock::get()?.unix_timestamp;
+    if timestamp < ctx.accounts.event_config.next_event_start {
+        return err!(Error::EventNotStarted);
+    }
+
+    let event_config_key = ctx.accounts.event_config.key();
+    let vault_bump = [*ctx.bumps.get("vault").unwrap()];
+    let vault_seeds: &[&[u8]] = &[b"vault".as_ref(), event_config_key.as_ref(), &vault_bump];
+
+    // Fund the next run of the create thread
+    transfer_sol(
+        &ctx.accounts.vault.to_account_info(),
+        &ctx.accounts.create_thread.to_account_info(),
+        &ctx.accounts.system_program.to_account_info(),
+        Some(vault_seeds),
+        THREAD_FEE,
+    )?;
+
+    let event_config = &mut ctx.accounts.event_config;
+    let interval = event_config.interval_seconds as i64;
+    if event_config.next_event_start + interval < timestamp {
+        // The thread fell behind so the event would already be locked. Skip to the next start that
+        // is still ahead and give back the rent for the event that was just initialized.
+        let missed_events = (timestamp - event_config.next_event_start) / interval + 1;
+        event_config.next_event_start += missed_events * interval;
+        msg!("skipped {} events, next event start: {}", missed_events, event_config.next_event_start);
+
+        ctx.accounts.event.close(ctx.accounts.payer.to_account_info())?;
+
+        return Ok(ThreadResponse {
+            kickoff_instruction: Some(create_next_event_ix(event_config).into()),
+            next_instruction: None,
+        });
+    }
+
+    // The event rent has already been taken from the payer by the init constraint
+    let event_rent = ctx.accounts.event.to_account_info().lamports();
+    let payer_lamports_pre = ctx.accounts.payer.lamports();
+    let event_clone = ctx.accounts.event.to_account_info().clone();
+    let fee_bps = ctx.accounts.event_config.fee_bps;
+    let fee_burn_bps = ctx.accounts.event_config.fee_burn_bps;
+    let initial_liquidity = ctx.accounts.event_config.initial_liquidity;
+
+    start_event(
+        &mut ctx.accounts.event_config,
+        &mut ctx.accounts.event,
+        *ctx.bumps.get("event").unwrap(),
+        StartEventAccounts {
+            payer: &ctx.accounts.payer.to_account_info(),
+            pyth_feed: &ctx.accounts.pyth_feed.to_account_info(),
+            fee_account: &ctx.accounts.fee_account.to_account_info(),
+            lock_thread: &ctx.accounts.lock_thread.to_account_info(),
+            settle_thread: &ctx.accounts.settle_thread.to_account_info(),
+            clockwork: &ctx.accounts.clockwork.to_account_info(),
+            system_program: &ctx.accounts.system_program.to_account_info(),
+        },
+        fee_bps,
+        fee_burn_bps,
+        initial_liquidity,
+    )?;
+
+    let event_config = &ctx.accounts.event_config;
+    if initial_liquidity > 0 {
+        if is_native_mint(event_config.currency_mint) {
+            transfer_sol(
+                &ctx.accounts.vault.to_account_info(),
+                &event_clone,
+                &ctx.accounts.system_program.to_account_info(),
+                Some(vault_seeds),
+                initial_liquidity,
+            )?;
+        } else {
+            let remaining_accounts = &mut ctx.remaining_accounts.iter();
+            let vault_currency_account = next_account_info(remaining_accounts)?;
+            let event_currency_account = next_account_info(remaining_accounts)?;
+            let token_program = next_account_info(remaining_accounts)?;
+            let ata_program = next_account_info(remaining_accounts)?;
+
+            transfer(
+                &ctx.accounts.vault.to_account_info(),
+                &event_clone,
+                vault_currency_account.into(),
+                event_currency_account.into(),
+                Option::from(&ctx.accounts.currency_mint.to_account_info()),
+                Option::from(&ctx.accounts.payer.to_account_info()),
+                ata_program.into(),
+                token_program.into(),
+                &ctx.accounts.system_program.to_account_info(),
+                Option::from(&ctx.accounts.rent.to_account_info()),
+                Some(vault_seeds),
+                None,
+                initial_liquidity,
+            )?;
+        }
+    }
+
+    // Pay back everything the payer spent on accounts and thread fees from the vault
+    let payer_spent = payer_lamports_pre
+        .checked_sub(ctx.accounts.payer.lamports())
+        .ok_or(Error::OverflowError)?
+        .checked_add(event_rent)
+        .ok_or(Error::OverflowError)?;
+    msg!("reimbursing payer: {}", payer_spent);
+
+    transfer_sol(
+        &ctx.accounts.vault.to_account_info(),
+        &ctx.accounts.payer.to_account_info(),
+        &ctx.accounts.system_program.to_account_info(),
+        Some(vault_seeds),
+        payer_spent,
+    )?;
+
+    Ok(ThreadResponse {
+        kickoff_instruction: Some(create_next_event_ix(event_config).into()),
+        next_instruction: None,
+    })
+}
+
+/// Builds the `create_next_event` instruction for the event that will start at the config's
+/// `next_event_start`. The payer is left for clockwork to fill in with its worker.
+pub fn create_next_event_ix(event_config: &Account<EventConfig>) -> Instruction {
+    let event_config_key = event_config.key();
+    let (event, _) = Pubkey::find_program_address(
+        &[
+            b"event".as_ref(),
+            event_config_key.as_ref(),
+            &event_config.next_event_start.to_le_bytes()
+        ],
+        &crate::ID
+    );
+    let (vault, _) = Pubkey::find_program_address(
+        &[b"vault".as_ref(), event_config_key.as_ref()],
+        &crate::ID
+    );
+
+    let mut accounts = vec![
+        AccountMeta::new(PAYER_PUBKEY, true),
+        AccountMeta::new(event_config_key, false),
+        AccountMeta::new(event, false),
+        AccountMeta::new(vault, false),
+        AccountMeta::new(event_config.create_thread, false),
+        AccountMeta::new_readonly(event_config.pyth_feed, false),
+        AccountMeta::new_readonly(event_config.fee_account, false),
+        AccountMeta::new_readonly(event_config.currency_mint, false),
+        AccountMeta::new(Thread::pubkey(event, "event_lock".into()), false),
+        AccountMeta::new(Thread::pubkey(event, "event_settle".into()), false),
+        AccountMeta::new_readonly(thread_program_ID, false),
+        AccountMeta::new_readonly(System::id(), false),
+        AccountMeta::new_readonly(solana_program::sysvar::rent::ID, false),
+    ];
+
+    if !is_native_mint(event_config.currency_mint) {
+        accounts.extend([
+            AccountMeta::new(get_associated_token_address(&vault, &event_config.currency_mint), false),
+            AccountMeta::new(get_associated_token_address(&event, &event_config.currency_mint), false),
+            AccountMeta::new_readonly(anchor_spl::token::ID, false),
+            AccountMeta::new_readonly(anchor_spl::associated_token::ID, false),
+        ]);
+    }
+
+    Instruction {
+        program_id: crate::ID,
+        accounts,
+        data: clockwork_sdk::utils::anchor_sighash("create_next_event").into(),
+    }
+}
+
+/// Points the config's create thread at the event starting at `next_event_start`. When the
+/// schedule is updated the payer tops up the thread rent since the config can't fund a realloc.
+pub fn update_create_thread<'info>(
+    event_config: &Account<'info, EventConfig>,
+    payer: &AccountInfo<'info>,
+    create_thread: &AccountInfo<'info>,
+    clockwork: &AccountInfo<'info>,
+    system_program: &AccountInfo<'info>,
+    update_schedule: bool,
+) -> Result<()> {
+    // Configs created before the create thread existed have nothing to update
+    if !event_config.has_create_thread() || create_thread.data_is_empty() {
+        return Ok(());
+    }
+
+    let trigger = if update_schedule {
+        let schedule = get_interval_schedule(
+            event_config.next_event_start,
+            event_config.interval_seconds
+        ).ok_or(Error::InvalidInterval)?;
+
+        let minimum_rent = Rent::get()?.minimum_balance(create_thread.data_len() + schedule.len());
+        if minimum_rent > create_thread.lamports() {
+            transfer_sol(
+                payer,
+                create_thread,
+                system_program,
+                None,
+                minimum_rent - create_thread.lamports(),
+            )?;
+        }
+
+        Some(Trigger::Cron {
+            schedule,
+            skippable: true,
+        })
+    } else {
+        None
+    };
+
+    let auth_seeds = event_config.auth_seeds();
+    thread_update(
+        CpiContext::new_with_signer(
+            clockwork.to_account_info(),
+            ThreadUpdate {
+                authority: event_config.to_account_info(),
+                thread: create_thread.to_account_info(),
+                system_program: system_program.to_account_info(),
+            },
+            &[&auth_seeds],
+        ),
+        ThreadSettings {
+            fee: None,
+            kickoff_instruction: Some(create_next_event_ix(event_config).into()),
+            rate_limit: None,
+            trigger,
+        },
+    )
+}
@@ -4,6 +4,7 @@ use crate::error::Error;
 use crate::util::{transfer, transfer_sol, is_native_mint};
 
 #[derive(Accounts)]
+#[instruction(outcome: Outcome)]
 pub struct CreateOrder<'info> {
     #[account(mut)]
     pub authority: Signer<'info>,
@@ -34,7 +35,12 @@ pub struct CreateOrder<'info> {
 
     #[account(
         init,
-        seeds = [b"order".as_ref(), event.key().as_ref(), authority.key().as_ref()],
+        seeds = [
+            b"order".as_ref(),
+            event.key().as_ref(),
+            authority.key().as_ref(),
+            &[outcome as u8]
+        ],
         bump,
         space = ORDER_SIZE,
         payer = authority,
@@ -47,10 +53,64 @@ pub struct CreateOrder<'info> {
 pub fn create_order<'info>(
     ctx: Context<'_, '_, '_, 'info, CreateOrder<'info>>,
     outcome: Outcome,
-    amount: u64
+    amount: u64,
+    referrer: Option<Pubkey>,
 ) -> Result<()> {
+    // Referrals from other wallets are only paid to referrers approved by the config authority
+    let referrer = referrer.unwrap_or_default();
+    if referrer == ctx.accounts.authority.key() {
+        return err!(Error::InvalidReferrer);
+    }
+
+    let order = &mut ctx.accounts.order;
+    order.bump = [*ctx.bumps.get("order").unwrap()];
+    order.authority = ctx.accounts.authority.key();
+    order.event = ctx.accounts.event.key();
+    order.outcome = outcome;
+    order.amount = amount;
+    order.referrer = referrer;
+
+    add_order_amount(
+        &ctx.accounts.authority.to_account_info(),
+        &ctx.accounts.event_config,
+        &mut ctx.accounts.event,
+        &ctx.accounts.system_program.to_account_info(),
+        ctx.remaining_accounts,
+        outcome,
+        amount,
+    )?;
+
     let event = &mut ctx.accounts.event;
+    if outcome == Outcome::Up {
+        event.up_count += 1;
+    } else {
+        event.down_count += 1;
+    }
+
+    emit!(OrderCreated {
+        event_config: ctx.accounts.event_config.key(),
+        event: ctx.accounts.event.key(),
+        order: ctx.accounts.order.key(),
+        authority: ctx.accounts.authority.key(),
+        outcome,
+        amount,
+        referrer,
+    });
 
+    Ok(())
+}
+
+/// Adds the amount to the event's pool for the outcome and moves it from the authority into the
+/// event.
+pub fn add_order_amount<'info>(
+    authority: &AccountInfo<'info>,
+    event_config: &Account<'info, EventConfig>,
+    event: &mut Account<'info, Event>,
+    system_program: &AccountInfo<'info>,
+    remaining_accounts: &[AccountInfo<'info>],
+    outcome: Outcome,
+    amount: u64,
+) -> Result<()> {
     let timestamp = Clock::get()?.unix_timestamp;
     if timestamp < event.start_time {
         return err!(Error::EventNotStarted);
@@ -69,34 +129,25 @@ pub fn create_order<'info>(
         return err!(Error::InvalidOutcome);
     }
 
-    let order = &mut ctx.accounts.order;
-    order.bump = [*ctx.bumps.get("order").unwrap()];
-    order.authority = ctx.accounts.authority.key();
-    order.event = event.key();
-    order.outcome = outcome;
-    order.amount = amount;
-
     if outcome == Outcome::Up {
         event.up_amount = event.up_amount.checked_add(amount as u128)
             .ok_or(Error::OverflowError)?;
-        event.up_count += 1;
     }
     else {
         event.down_amount = event.down_amount.checked_add(amount as u128)
             .ok_or(Error::OverflowError)?;
-        event.down_count += 1;
     }
 
-    if is_native_mint(ctx.accounts.event_config.currency_mint) {
+    if is_native_mint(event_config.currency_mint) {
         transfer_sol(
-            &ctx.accounts.authority.to_account_info(),
+            authority,
             &event.to_account_info(),
-            &ctx.accounts.system_program.to_account_info(),
+            system_program,
             None,
             amount,
         )?;
     } else {
-        let remaining_accounts = &mut ctx.remaining_accounts.iter();
+        let remaining_accounts = &mut remaining_accounts.iter();
         let currency_mint = next_account_info(remaining_accounts)?;
         let event_currency_account = next_account_info(remaining_accounts)?;
         let user_currency_account = next_account_info(remaining_accounts)?;
@@ -104,20 +155,20 @@ pub fn create_order<'info>(
         let ata_program = next_account_info(remaining_accounts)?;
         let rent = next_account_info(remaining_accounts)?;
 
-        if ctx.accounts.event_config.currency_mint != currency_mint.key() {
+        if event_config.currency_mint != currency_mint.key() {
             return err!(Error::InvalidMint);
         }
 
         transfer(
-            &ctx.accounts.authority.to_account_info(),
+            authority,
             &event.to_account_info(),
             user_currency_account.into(),
             event_currency_account.into(),
             currency_mint.into(),
-            Option::from(&ctx.accounts.authority.to_account_info()),
+            Option::from(authority),
             ata_program.into(),
             token_program.into(),
-            &ctx.accounts.system_program.to_account_info(),
+            system_program,
             rent.into(),
             None,
             None,
@@ -126,4 +177,16 @@ pub fn create_order<'info>(
     }
 
     Ok(())
+}
+
+#[event]
+pub struct OrderCreated {
+    pub event_config: Pubkey,
+    pub event: Pubkey,
+    pub order: Pubkey,
+    pub authority: Pubkey,
+    pub outcome: Outcome,
+    pub amount: u64,
+    /// Account that referred the order or the default pubkey if none
+    pub referrer: Pubkey,
 }
\ No newline at end of file
@@ -0,0 +1,55 @@
+use anchor_lang::prelude::*;
+use crate::state::{EventConfig, Referrer, REFERRER_SIZE};
+use crate::error::Error;
+
+#[derive(Accounts)]
+pub struct CreateReferrer<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    /// CHECK: Any account approved by the config authority can refer orders
+    #[account()]
+    pub authority: UncheckedAccount<'info>,
+
+    pub config_authority: Signer<'info>,
+
+    #[account(
+        seeds = [
+            b"event_config".as_ref(),
+            event_config.authority.as_ref(),
+            event_config.pyth_feed.as_ref(),
+            event_config.currency_mint.as_ref()
+        ],
+        bump = event_config.bump[0],
+        constraint = event_config.authority == config_authority.key() @ Error::InvalidReferrer,
+    )]
+    pub event_config: Box<Account<'info, EventConfig>>,
+
+    #[account(
+        init,
+        seeds = [
+            b"referrer".as_ref(),
+            event_config.key().as_ref(),
+            authority.key().as_ref()
+        ],
+        bump,
+        space = REFERRER_SIZE,
+        payer = payer,
+    )]
+    pub referrer: Box<Account<'info, Referrer>>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/// Approves a referrer for a config by creating the account tracking its lifetime totals. Only the
+/// config authority can approve referrers, so users can't collect referral rewards on their own
+/// orders by referring them from a second wallet. Orders referred by anyone else pay the reward
+/// to the fee instead. Anyone can pay for the account.
+pub fn create_referrer<'info>(ctx: Context<'_, '_, '_, 'info, CreateReferrer<'info>>) -> Result<()> {
+    let referrer = &mut ctx.accounts.referrer;
+    referrer.bump = [*ctx.bumps.get("referrer").unwrap()];
+    referrer.event_config = ctx.accounts.event_config.key();
+    referrer.authority = ctx.accounts.authority.key();
+
+    Ok(())
+}
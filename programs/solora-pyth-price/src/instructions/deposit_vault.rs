@@ -0,0 +1,106 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::Mint;
+use crate::state::EventConfig;
+use crate::util::{is_native_mint, transfer, transfer_sol};
+
+#[derive(Accounts)]
+pub struct DepositVault<'info> {
+    #[account(mut)]
+    pub depositor: Signer<'info>,
+
+    #[account(
+        seeds = [
+            b"event_config".as_ref(),
+            event_config.authority.as_ref(),
+            event_config.pyth_feed.as_ref(),
+            event_config.currency_mint.as_ref()
+        ],
+        bump = event_config.bump[0],
+        has_one = currency_mint
+    )]
+    pub event_config: Box<Account<'info, EventConfig>>,
+
+    pub currency_mint: Account<'info, Mint>,
+
+    #[account(
+        mut,
+        seeds = [b"vault".as_ref(), event_config.key().as_ref()],
+        bump
+    )]
+    pub vault: SystemAccount<'info>,
+
+    pub system_program: Program<'info, System>,
+    pub rent: Sysvar<'info, Rent>,
+}
+
+/// Funds the vault that events created by the config's thread are paid from. `lamports` covers
+/// account rent and thread fees, `amount` is currency for `initial_liquidity`, which is the same
+/// as lamports for native configs. SPL configs pass the depositor's currency account, the vault
+/// currency account, the token program and the associated token program as remaining accounts.
+pub fn deposit_vault<'info>(
+    ctx: Context<'_, '_, '_, 'info, DepositVault<'info>>,
+    lamports: u64,
+    amount: u64,
+) -> Result<()> {
+    if lamports > 0 {
+        transfer_sol(
+            &ctx.accounts.depositor.to_account_info(),
+            &ctx.accounts.vault.to_account_info(),
+            &ctx.accounts.system_program.to_account_info(),
+            None,
+            lamports,
+        )?;
+    }
+
+    if amount > 0 {
+        if is_native_mint(ctx.accounts.currency_mint.key()) {
+            transfer_sol(
+                &ctx.accounts.depositor.to_account_info(),
+                &ctx.accounts.vault.to_account_info(),
+                &ctx.accounts.system_program.to_account_info(),
+                None,
+                amount,
+            )?;
+        } else {
+            let remaining_accounts = &mut ctx.remaining_accounts.iter();
+            let depositor_currency_account = next_account_info(remaining_accounts)?;
+            let vault_currency_account = next_account_info(remaining_accounts)?;
+            let token_program = next_account_info(remaining_accounts)?;
+            let ata_program = next_account_info(remaining_accounts)?;
+
+            transfer(
+                &ctx.accounts.depositor.to_account_info(),
+                &ctx.accounts.vault.to_account_info(),
+                depositor_currency_account.into(),
+                vault_currency_account.into(),
+                Option::from(&ctx.accounts.currency_mint.to_account_info()),
+                Option::from(&ctx.accounts.depositor.to_account_info()),
+                ata_program.into(),
+                token_program.into(),
+                &ctx.accounts.system_program.to_account_info(),
+                Option::from(&ctx.accounts.rent.to_account_info()),
+                None,
+                None,
+                amount,
+            )?;
+        }
+    }
+
+    emit!(VaultDeposited {
+        event_config: ctx.accounts.event_config.key(),
+        depositor: ctx.accounts.depositor.key(),
+        lamports,
+        amount,
+    });
+
+    Ok(())
+}
+
+#[event]
+pub struct VaultDeposited {
+    pub event_config: Pubkey,
+    pub depositor: Pubkey,
+    pub lamports: u64,
+    /// Currency deposited for initial liquidity
+    pub amount: u64,
+}
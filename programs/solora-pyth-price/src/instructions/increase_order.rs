@@ -0,0 +1,93 @@
+use anchor_lang::prelude::*;
+use crate::state::{Event, EventConfig, Order, Outcome};
+use crate::error::Error;
+use crate::instructions::add_order_amount;
+
+#[derive(Accounts)]
+pub struct IncreaseOrder<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    #[account(
+        seeds = [
+            b"event_config".as_ref(),
+            event_config.authority.as_ref(),
+            event_config.pyth_feed.as_ref(),
+            event_config.currency_mint.as_ref()
+        ],
+        bump = event_config.bump[0],
+    )]
+    pub event_config: Box<Account<'info, EventConfig>>,
+
+    #[account(
+        mut,
+        seeds = [
+            b"event".as_ref(),
+            event_config.key().as_ref(),
+            &event.start_time.to_le_bytes()
+        ],
+        bump = event.bump[0],
+        constraint = event.outcome == Outcome::Undrawn @ Error::EventSettled,
+        has_one = event_config
+    )]
+    pub event: Box<Account<'info, Event>>,
+
+    #[account(
+        mut,
+        constraint = order.is_order_address(&order.key(), &crate::ID) @ Error::InvalidOrderAccounts,
+        has_one = event,
+        has_one = authority
+    )]
+    pub order: Box<Account<'info, Order>>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/// Adds to an existing order before the event locks. The outcome must match the order's outcome.
+pub fn increase_order<'info>(
+    ctx: Context<'_, '_, '_, 'info, IncreaseOrder<'info>>,
+    outcome: Outcome,
+    amount: u64
+) -> Result<()> {
+    if outcome != ctx.accounts.order.outcome {
+        return err!(Error::OutcomeMismatch);
+    }
+
+    add_order_amount(
+        &ctx.accounts.authority.to_account_info(),
+        &ctx.accounts.event_config,
+        &mut ctx.accounts.event,
+        &ctx.accounts.system_program.to_account_info(),
+        ctx.remaining_accounts,
+        outcome,
+        amount,
+    )?;
+
+    let order = &mut ctx.accounts.order;
+    order.amount = order.amount.checked_add(amount)
+        .ok_or(Error::OverflowError)?;
+
+    emit!(OrderIncreased {
+        event_config: ctx.accounts.event_config.key(),
+        event: ctx.accounts.event.key(),
+        order: order.key(),
+        authority: order.authority,
+        outcome,
+        amount,
+        total_amount: order.amount,
+    });
+
+    Ok(())
+}
+
+#[event]
+pub struct OrderIncreased {
+    pub event_config: Pubkey,
+    pub event: Pubkey,
+    pub order: Pubkey,
+    pub authority: Pubkey,
+    pub outcome: Outcome,
+    /// Amount added to the order
+    pub amount: u64,
+    pub total_amount: u64,
+}
@@ -0,0 +1,56 @@
+use anchor_lang::prelude::*;
+use anchor_lang::Discriminator;
+use crate::state::{EventConfig, EVENT_CONFIG_SIZE, EVENT_CONFIG_VERSION};
+use crate::error::Error;
+use crate::util::transfer_sol;
+
+/// Offset of `EventConfig.version` after the discriminator and bump
+const VERSION_OFFSET: usize = 8 + 1;
+
+#[derive(Accounts)]
+pub struct MigrateEventConfig<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    /// CHECK: Configs allocated before the current layout may not deserialize, so the owner and
+    /// discriminator are checked instead
+    #[account(mut, owner = crate::ID)]
+    pub event_config: UncheckedAccount<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/// Grows a config allocated with an older `EVENT_CONFIG_SIZE` to the current size so it can be
+/// read with the current layout. Fields added since read as zero, which keeps the old behaviour.
+/// Anyone can pay for the migration since nothing else about the config changes.
+pub fn migrate_event_config<'info>(
+    ctx: Context<'_, '_, '_, 'info, MigrateEventConfig<'info>>,
+) -> Result<()> {
+    let event_config = &ctx.accounts.event_config;
+    {
+        let data = event_config.try_borrow_data()?;
+        if data.len() <= VERSION_OFFSET || data[..8] != EventConfig::discriminator() {
+            return err!(Error::InvalidEventConfig);
+        }
+    }
+
+    if event_config.data_len() < EVENT_CONFIG_SIZE {
+        let rent = Rent::get()?.minimum_balance(EVENT_CONFIG_SIZE);
+        let lamports = rent.saturating_sub(event_config.lamports());
+        if lamports > 0 {
+            transfer_sol(
+                &ctx.accounts.payer.to_account_info(),
+                &event_config.to_account_info(),
+                &ctx.accounts.system_program.to_account_info(),
+                None,
+                lamports,
+            )?;
+        }
+
+        event_config.realloc(EVENT_CONFIG_SIZE, true)?;
+    }
+
+    event_config.try_borrow_mut_data()?[VERSION_OFFSET] = EVENT_CONFIG_VERSION;
+
+    Ok(())
+}
@@ -1,8 +1,8 @@
 use crate::error::Error;
 use crate::state::{Event, EventConfig, Outcome};
-use crate::util::get_price_with_decimal_change;
+use crate::oracle::get_event_price;
+use crate::util::{get_price_with_decimal_change, is_conf_too_wide};
 use anchor_lang::prelude::*;
-use pyth_sdk_solana::load_price_feed_from_account_info;
 
 #[derive(Accounts)]
 pub struct SetLockPrice<'info> {
@@ -44,15 +44,21 @@ pub fn set_lock_price<'info>(ctx: Context<'_, '_, '_, 'info, SetLockPrice<'info>
         return err!(Error::EventNotLocked);
     }
 
-    if timestamp - event.lock_time > 15 {
+    if timestamp - event.lock_time > event.get_grace_period() {
         msg!("Locking period expired: {} {}", timestamp, event.lock_time);
         event.outcome = Outcome::Invalid;
     } else {
         // TODO: Delete lock thread when possible to delete from thread call
 
-        let price_feed =
-            load_price_feed_from_account_info(&ctx.accounts.pyth_feed.to_account_info()).unwrap();
-        let price = price_feed.get_price_no_older_than(timestamp, 30);
+        let price = get_event_price(
+            ctx.accounts.event_config.oracle_kind,
+            &ctx.accounts.event_config.feed_id,
+            &ctx.accounts.pyth_feed.to_account_info(),
+            timestamp,
+            event.lock_time,
+            event.publish_time_tolerance,
+            event.get_max_price_age(),
+        )?;
 
         // Users will need to be able to withdraw funds if the price feed is not available, so set invalid outcome
         if price.is_some() {
@@ -60,9 +66,15 @@ pub fn set_lock_price<'info>(ctx: Context<'_, '_, '_, 'info, SetLockPrice<'info>
             if price.price < 0 {
                 msg!("Negative price: {}", price.price);
                 event.outcome = Outcome::Invalid;
+            } else if is_conf_too_wide(price.price, price.conf, event.max_conf_bps) {
+                msg!("Confidence too wide: {} {}", price.price, price.conf);
+                event.outcome = Outcome::Invalid;
             } else {
                 event.lock_price =
                     get_price_with_decimal_change(price.price, price.expo, event.price_decimals)?;
+                event.lock_conf =
+                    get_price_with_decimal_change(price.conf as i64, price.expo, event.price_decimals)?;
+                event.lock_publish_time = price.publish_time;
             }
         } else {
             msg!("No price found");
@@ -74,6 +86,8 @@ pub fn set_lock_price<'info>(ctx: Context<'_, '_, '_, 'info, SetLockPrice<'info>
         event_config: event.event_config,
         event: event.key(),
         lock_price: event.lock_price,
+        lock_conf: event.lock_conf,
+        lock_publish_time: event.lock_publish_time,
         up_amount: event.up_amount,
         down_amount: event.down_amount,
         up_count: event.up_count,
@@ -89,6 +103,8 @@ pub struct EventLocked {
     pub event_config: Pubkey,
     pub event: Pubkey,
     pub lock_price: u64,
+    pub lock_conf: u64,
+    pub lock_publish_time: i64,
     pub up_amount: u128,
     pub down_amount: u128,
     pub up_count: u32,
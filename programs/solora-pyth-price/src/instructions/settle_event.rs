@@ -1,8 +1,12 @@
 use anchor_lang::prelude::*;
-use pyth_sdk_solana::load_price_feed_from_account_info;
 use crate::state::{Event, EventConfig, Outcome};
 use crate::error::Error;
-use crate::util::get_price_with_decimal_change;
+use crate::oracle::get_event_price;
+use crate::util::{
+    get_price_outcome,
+    get_price_with_decimal_change,
+    is_conf_too_wide,
+};
 
 #[derive(Accounts)]
 pub struct SettleEvent<'info> {
@@ -45,14 +49,21 @@ pub fn settle_event<'info>(
         return err!(Error::EventInWaitingPeriod);
     }
 
-    if timestamp - (event.lock_time + event.wait_period as i64) > 15 {
+    if timestamp - (event.lock_time + event.wait_period as i64) > event.get_grace_period() {
         msg!("Settle period expired: {} {}", timestamp, event.lock_time);
         event.outcome = Outcome::Invalid;
     } else {
         // TODO: Delete settle thread when possible to delete from thread call
 
-        let price_feed = load_price_feed_from_account_info(&ctx.accounts.pyth_feed.to_account_info()).unwrap();
-        let price = price_feed.get_price_no_older_than(timestamp, 30);
+        let price = get_event_price(
+            ctx.accounts.event_config.oracle_kind,
+            &ctx.accounts.event_config.feed_id,
+            &ctx.accounts.pyth_feed.to_account_info(),
+            timestamp,
+            event.lock_time + event.wait_period as i64,
+            event.publish_time_tolerance,
+            event.get_max_price_age(),
+        )?;
 
         // Users will need to be able to withdraw funds if the price feed is not available, so set invalid outcome
         if price.is_some() {
@@ -60,20 +71,29 @@ pub fn settle_event<'info>(
             if price.price < 0 {
                 msg!("Negative price: {}", price.price);
                 event.outcome = Outcome::Invalid;
+            } else if is_conf_too_wide(price.price, price.conf, event.max_conf_bps) {
+                msg!("Confidence too wide: {} {}", price.price, price.conf);
+                event.outcome = Outcome::Invalid;
             } else {
                 event.settle_price = get_price_with_decimal_change(
                     price.price,
                     price.expo,
                     event.price_decimals
                 )?;
+                event.settle_conf = get_price_with_decimal_change(
+                    price.conf as i64,
+                    price.expo,
+                    event.price_decimals
+                )?;
+                event.settle_publish_time = price.publish_time;
 
-                event.outcome = if event.settle_price == event.lock_price {
-                    Outcome::Same
-                } else if event.settle_price > event.lock_price {
-                    Outcome::Up
+                // Moves the oracle can't tell apart from noise refund everyone
+                let band = if event.same_band {
+                    event.lock_conf.max(event.settle_conf)
                 } else {
-                    Outcome::Down
+                    0
                 };
+                event.outcome = get_price_outcome(event.lock_price, event.settle_price, band);
             }
         } else {
             msg!("No price found");
@@ -85,6 +105,8 @@ pub fn settle_event<'info>(
         event_config: event.event_config,
         event: event.key(),
         settle_price: event.settle_price,
+        settle_conf: event.settle_conf,
+        settle_publish_time: event.settle_publish_time,
         outcome: event.outcome
     });
     Ok(())
@@ -95,5 +117,7 @@ pub struct EventSettled {
     pub event_config: Pubkey,
     pub event: Pubkey,
     pub settle_price: u64,
+    pub settle_conf: u64,
+    pub settle_publish_time: i64,
     pub outcome: Outcome
 }
\ No newline at end of file
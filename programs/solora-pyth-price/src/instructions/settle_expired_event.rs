@@ -1,6 +1,7 @@
 use anchor_lang::prelude::*;
 use crate::state::{Event, EventConfig, Outcome};
 use crate::error::Error;
+use crate::instructions::EventSettled;
 
 #[derive(Accounts)]
 pub struct SettleExpiredEvent<'info> {
@@ -49,16 +50,10 @@ pub fn settle_expired_event<'info>(
         event_config: event.event_config,
         event: event.key(),
         settle_price: 0,
+        settle_conf: 0,
+        settle_publish_time: 0,
         outcome: event.outcome
     });
 
     Ok(())
 }
-
-#[event]
-pub struct EventSettled {
-    pub event_config: Pubkey,
-    pub event: Pubkey,
-    pub settle_price: u64,
-    pub outcome: Outcome
-}
\ No newline at end of file
@@ -1,5 +1,5 @@
 use crate::error::Error;
-use crate::state::{Event, EventConfig, Order, Outcome};
+use crate::state::{Event, EventConfig, FeeRecipient, Order, Outcome, Referrer};
 use crate::util::{is_native_mint, transfer, transfer_sol_pda};
 use anchor_lang::prelude::*;
 use anchor_spl::token;
@@ -7,8 +7,13 @@ use anchor_spl::token::Burn;
 
 #[derive(Accounts)]
 pub struct SettleOrder<'info> {
+    /// Anyone can settle an order, winnings and rent always go to the order authority
     #[account(mut)]
-    pub authority: Signer<'info>,
+    pub payer: Signer<'info>,
+
+    /// CHECK: Safe due to order constraint
+    #[account(mut)]
+    pub authority: UncheckedAccount<'info>,
 
     #[account(
         seeds = [
@@ -30,6 +35,7 @@ pub struct SettleOrder<'info> {
         ],
         bump = event.bump[0],
         constraint = event.outcome != Outcome::Undrawn @ Error::EventNotSettled,
+        constraint = !event.claim_expired @ Error::ClaimPeriodExpired,
         has_one = fee_account,
         has_one = event_config
     )]
@@ -38,8 +44,7 @@ pub struct SettleOrder<'info> {
     #[account(
         mut,
         close = authority,
-        seeds = [b"order".as_ref(), event.key().as_ref(), authority.key().as_ref()],
-        bump = order.bump[0],
+        constraint = order.is_order_address(&order.key(), &crate::ID) @ Error::InvalidOrderAccounts,
         has_one = event,
         has_one = authority
     )]
@@ -53,36 +58,49 @@ pub struct SettleOrder<'info> {
     pub rent: Sysvar<'info, Rent>,
 }
 
+/// Settles an order and closes it, paying out to the order authority. The payer doesn't need to be
+/// the order authority so keepers can wind events down. A payer settling someone else's order
+/// pays for any associated token accounts and, once the crank reward delay has passed, receives
+/// the config's crank reward share of the fee.
+///
+/// SPL remaining accounts are the currency mint, event currency account, user currency account,
+/// fee currency account, token program and ATA program, followed by the payer currency account
+/// when a crank reward is paid. When a referral reward is paid the referrer and its referrer
+/// account come next, plus the referrer currency account for SPL events. The reward goes to the
+/// fee instead if the referrer account was never created. Native events then need
+/// the fee burn account when part of the fee is burned. The event's fee recipients come last, each
+/// followed by its currency account for SPL events.
 pub fn settle_order<'info>(ctx: Context<'_, '_, '_, 'info, SettleOrder<'info>>) -> Result<()> {
     let event_config = &ctx.accounts.event_config;
     let is_native = is_native_mint(event_config.currency_mint);
     let event = &ctx.accounts.event;
     let order = &ctx.accounts.order;
-
-    let earned_amount = get_earned_amount(
-        event.up_amount,
-        event.down_amount,
-        event.outcome,
-        order.amount,
-        order.outcome
-    )?;
-    msg!("earned_amount: {}", earned_amount);
-
-    // Only take fees on earned amounts
-    let mut fee = get_bps_amount(earned_amount, event.fee_bps)?;
-    msg!("fee: {}", fee);
-
-    let amount_to_user = get_amount_to_user(
-        event.outcome,
-        event.up_amount,
-        event.down_amount,
-        order.outcome,
-        order.amount,
+    let timestamp = Clock::get()?.unix_timestamp;
+    let payer_info = ctx.accounts.payer.to_account_info();
+    let system_program_info = ctx.accounts.system_program.to_account_info();
+    let rent_info = ctx.accounts.rent.to_account_info();
+    let payer_accounts = PayerAccounts {
+        payer: &payer_info,
+        system_program: &system_program_info,
+        rent: &rent_info,
+    };
+
+    let OrderPayout {
         earned_amount,
-        fee
+        mut fee,
+        crank_reward,
+        mut referral_reward,
+        amount_to_user
+    } = get_order_payout(
+        event,
+        order,
+        event_config.crank_reward_bps,
+        ctx.accounts.payer.key() != ctx.accounts.authority.key() &&
+            event.is_crank_reward_period(timestamp)
     )?;
-    msg!("amount_to_user: {}", amount_to_user);
+    let mut fee_burned = 0;
 
+    let remaining_accounts = &mut ctx.remaining_accounts.iter();
     if amount_to_user > 0 {
         if is_native {
             transfer_sol_pda(
@@ -91,15 +109,52 @@ pub fn settle_order<'info>(ctx: Context<'_, '_, '_, 'info, SettleOrder<'info>>)
                 amount_to_user
             )?;
 
-            if fee > 0 {
+            if crank_reward > 0 {
+                transfer_sol_pda(
+                    &mut ctx.accounts.event.to_account_info(),
+                    &mut ctx.accounts.payer.to_account_info(),
+                    crank_reward
+                )?;
+            }
+
+            let unpaid_referral = pay_referral(
+                ctx.program_id,
+                event,
+                order,
+                None,
+                payer_accounts,
+                remaining_accounts,
+                referral_reward,
+            )?;
+            referral_reward -= unpaid_referral;
+            fee = fee.checked_add(unpaid_referral).ok_or(Error::OverflowError)?;
+
+            // SOL can't be burned so the burned share goes to the event's fee burn account
+            fee_burned = get_bps_amount(fee, event.get_native_fee_burn_bps())?;
+            if fee_burned > 0 {
+                let fee_burn_account = next_account_info(remaining_accounts)?;
+                if fee_burn_account.key() != event.fee_burn_account {
+                    return err!(Error::InvalidFeeBurnAccount);
+                }
+
                 transfer_sol_pda(
                     &mut ctx.accounts.event.to_account_info(),
-                    &mut ctx.accounts.fee_account.to_account_info(),
-                    fee
+                    &mut fee_burn_account.clone(),
+                    fee_burned
                 )?;
+                fee = fee.checked_sub(fee_burned).unwrap();
             }
+
+            pay_fee(
+                event,
+                &ctx.accounts.fee_account.to_account_info(),
+                None,
+                None,
+                payer_accounts,
+                remaining_accounts,
+                fee,
+            )?;
         } else {
-            let remaining_accounts = &mut ctx.remaining_accounts.iter();
             let currency_mint = next_account_info(remaining_accounts)?;
             let event_currency_account = next_account_info(remaining_accounts)?;
             let user_currency_account = next_account_info(remaining_accounts)?;
@@ -107,6 +162,10 @@ pub fn settle_order<'info>(ctx: Context<'_, '_, '_, 'info, SettleOrder<'info>>)
             let token_program = next_account_info(remaining_accounts)?;
             let ata_program = next_account_info(remaining_accounts)?;
 
+            if event_config.currency_mint != currency_mint.key() {
+                return err!(Error::InvalidMint);
+            }
+
             let start_time_bytes = &event.start_time.to_le_bytes();
             let auth_seeds = event.auth_seeds(start_time_bytes);
 
@@ -116,7 +175,7 @@ pub fn settle_order<'info>(ctx: Context<'_, '_, '_, 'info, SettleOrder<'info>>)
                 event_currency_account.into(),
                 user_currency_account.into(),
                 currency_mint.into(),
-                Option::from(&ctx.accounts.authority.to_account_info()),
+                Option::from(&ctx.accounts.payer.to_account_info()),
                 ata_program.into(),
                 token_program.into(),
                 &ctx.accounts.system_program.to_account_info(),
@@ -126,6 +185,42 @@ pub fn settle_order<'info>(ctx: Context<'_, '_, '_, 'info, SettleOrder<'info>>)
                 amount_to_user
             )?;
 
+            if crank_reward > 0 {
+                let payer_currency_account = next_account_info(remaining_accounts)?;
+                transfer(
+                    &ctx.accounts.event.to_account_info(),
+                    &ctx.accounts.payer.to_account_info(),
+                    event_currency_account.into(),
+                    payer_currency_account.into(),
+                    currency_mint.into(),
+                    Option::from(&ctx.accounts.payer.to_account_info()),
+                    ata_program.into(),
+                    token_program.into(),
+                    &ctx.accounts.system_program.to_account_info(),
+                    Option::from(&ctx.accounts.rent.to_account_info()),
+                    Some(&auth_seeds),
+                    None,
+                    crank_reward
+                )?;
+            }
+
+            let unpaid_referral = pay_referral(
+                ctx.program_id,
+                event,
+                order,
+                Some(EventCurrencyAccounts {
+                    currency_mint,
+                    event_currency_account,
+                    token_program,
+                    ata_program,
+                }),
+                payer_accounts,
+                remaining_accounts,
+                referral_reward,
+            )?;
+            referral_reward -= unpaid_referral;
+            fee = fee.checked_add(unpaid_referral).ok_or(Error::OverflowError)?;
+
             if fee > 0 {
                 // Burn fees if needed
                 if event.fee_burn_bps > 0 {
@@ -145,26 +240,24 @@ pub fn settle_order<'info>(ctx: Context<'_, '_, '_, 'info, SettleOrder<'info>>)
                         )?;
 
                         fee = fee.checked_sub(fee_burn_amount).unwrap();
+                        fee_burned = fee_burn_amount;
                     }
                 }
 
-                if fee > 0 {
-                    transfer(
-                        &ctx.accounts.event.to_account_info(),
-                        &ctx.accounts.fee_account.to_account_info(),
-                        event_currency_account.into(),
-                        fee_currency_account.into(),
-                        currency_mint.into(),
-                        Option::from(&ctx.accounts.authority.to_account_info()),
-                        ata_program.into(),
-                        token_program.into(),
-                        &ctx.accounts.system_program.to_account_info(),
-                        Option::from(&ctx.accounts.rent.to_account_info()),
-                        Some(&auth_seeds),
-                        None,
-                        fee,
-                    )?;
-                }
+                pay_fee(
+                    event,
+                    &ctx.accounts.fee_account.to_account_info(),
+                    Some(fee_currency_account),
+                    Some(EventCurrencyAccounts {
+                        currency_mint,
+                        event_currency_account,
+                        token_program,
+                        ata_program,
+                    }),
+                    payer_accounts,
+                    remaining_accounts,
+                    fee,
+                )?;
             }
         }
     }
@@ -172,9 +265,307 @@ pub fn settle_order<'info>(ctx: Context<'_, '_, '_, 'info, SettleOrder<'info>>)
     let event = &mut ctx.accounts.event;
     event.orders_settled += 1;
 
+    emit!(OrderSettled {
+        event_config: ctx.accounts.event_config.key(),
+        event: event.key(),
+        order: ctx.accounts.order.key(),
+        authority: ctx.accounts.authority.key(),
+        payer: ctx.accounts.payer.key(),
+        outcome: ctx.accounts.order.outcome,
+        event_outcome: event.outcome,
+        amount: ctx.accounts.order.amount,
+        earned_amount,
+        fee,
+        fee_burned,
+        crank_reward,
+        referrer: ctx.accounts.order.referrer,
+        referral_reward,
+        amount_to_user,
+    });
+
     Ok(())
 }
 
+#[event]
+pub struct OrderSettled {
+    pub event_config: Pubkey,
+    pub event: Pubkey,
+    pub order: Pubkey,
+    pub authority: Pubkey,
+    /// Account that settled the order
+    pub payer: Pubkey,
+    pub outcome: Outcome,
+    pub event_outcome: Outcome,
+    pub amount: u64,
+    pub earned_amount: u64,
+    /// Fee sent to the fee account, not including the burned amount or rewards
+    pub fee: u64,
+    pub fee_burned: u64,
+    /// Share of the fee paid to the payer for settling someone else's order
+    pub crank_reward: u64,
+    /// Account that referred the order or the default pubkey if none
+    pub referrer: Pubkey,
+    pub referral_reward: u64,
+    pub amount_to_user: u64,
+}
+
+/// Amounts paid out when settling an order
+pub struct OrderPayout {
+    pub earned_amount: u64,
+    /// Fee owed to the fee account, including any amount to burn but not the rewards
+    pub fee: u64,
+    pub crank_reward: u64,
+    pub referral_reward: u64,
+    pub amount_to_user: u64,
+}
+
+/// Computes the payout of an order for a settled event. `is_crank` should be set when the order is
+/// settled by someone other than its authority after the crank reward delay so the crank reward is
+/// taken out of the fee. Orders
+/// with a referrer also have the event's referral share taken out of the fee.
+pub fn get_order_payout(
+    event: &Event,
+    order: &Order,
+    crank_reward_bps: u32,
+    is_crank: bool,
+) -> Result<OrderPayout> {
+    let earned_amount = get_earned_amount(
+        event.up_amount,
+        event.down_amount,
+        event.outcome,
+        order.amount,
+        order.outcome
+    )?;
+    msg!("earned_amount: {}", earned_amount);
+
+    // Only take fees on earned amounts
+    let fee = get_bps_amount(earned_amount, event.fee_bps)?;
+    msg!("fee: {}", fee);
+
+    let amount_to_user = get_amount_to_user(
+        event.outcome,
+        event.up_amount,
+        event.down_amount,
+        order.outcome,
+        order.amount,
+        earned_amount,
+        fee
+    )?;
+    msg!("amount_to_user: {}", amount_to_user);
+
+    // Rewards come out of the fee so the order authority receives the same either way
+    let crank_reward = if is_crank {
+        get_bps_amount(fee, crank_reward_bps)?
+    } else {
+        0
+    };
+
+    let referral_reward = if order.has_referrer() {
+        get_bps_amount(fee, event.referral_fee_bps)?
+    } else {
+        0
+    };
+
+    Ok(OrderPayout {
+        earned_amount,
+        fee: fee
+            .checked_sub(crank_reward)
+            .ok_or(Error::OverflowError)?
+            .checked_sub(referral_reward)
+            .ok_or(Error::OverflowError)?,
+        crank_reward,
+        referral_reward,
+        amount_to_user,
+    })
+}
+
+/// SPL accounts needed to pay out of an event that doesn't use the native mint
+#[derive(Clone, Copy)]
+pub struct EventCurrencyAccounts<'a, 'info> {
+    pub currency_mint: &'a AccountInfo<'info>,
+    pub event_currency_account: &'a AccountInfo<'info>,
+    pub token_program: &'a AccountInfo<'info>,
+    pub ata_program: &'a AccountInfo<'info>,
+}
+
+/// Pays the event's fee recipients their share of `fee` and sends the rest to the fee account. The
+/// recipient accounts are read from `recipient_accounts` in the order they are set on the event,
+/// each followed by its currency account for SPL events.
+pub fn pay_fee<'a, 'info>(
+    event: &Account<'info, Event>,
+    fee_account: &AccountInfo<'info>,
+    fee_currency_account: Option<&AccountInfo<'info>>,
+    currency_accounts: Option<EventCurrencyAccounts<'_, 'info>>,
+    payer_accounts: PayerAccounts<'_, 'info>,
+    recipient_accounts: &mut std::slice::Iter<'a, AccountInfo<'info>>,
+    fee: u64,
+) -> Result<()> {
+    if fee == 0 {
+        return Ok(())
+    }
+
+    let (shares, remainder) = get_fee_shares(fee, &event.fee_recipients)?;
+    for (recipient, share) in event.fee_recipients.iter().zip(shares) {
+        let recipient_account = next_account_info(recipient_accounts)?;
+        if recipient_account.key() != recipient.account {
+            return err!(Error::InvalidFeeRecipient);
+        }
+
+        let recipient_currency_account = if currency_accounts.is_some() {
+            Some(next_account_info(recipient_accounts)?)
+        } else {
+            None
+        };
+
+        pay_from_event(
+            event,
+            recipient_account,
+            recipient_currency_account,
+            currency_accounts,
+            payer_accounts,
+            share,
+        )?;
+    }
+
+    pay_from_event(
+        event,
+        fee_account,
+        fee_currency_account,
+        currency_accounts,
+        payer_accounts,
+        remainder,
+    )
+}
+
+/// Pays an order's referral reward and adds it to the referrer's lifetime totals. The referrer
+/// and its referrer account are read from `referrer_accounts`, followed by the referrer's currency
+/// account for SPL events. Referrers are approved by creating their referrer account, so when it
+/// doesn't exist nothing is paid and the reward is returned to be added back to the fee.
+pub fn pay_referral<'a, 'info>(
+    program_id: &Pubkey,
+    event: &Account<'info, Event>,
+    order: &Order,
+    currency_accounts: Option<EventCurrencyAccounts<'_, 'info>>,
+    payer_accounts: PayerAccounts<'_, 'info>,
+    referrer_accounts: &mut std::slice::Iter<'a, AccountInfo<'info>>,
+    amount: u64,
+) -> Result<u64> {
+    if amount == 0 {
+        return Ok(0)
+    }
+
+    let referrer_account = next_account_info(referrer_accounts)?;
+    let referrer_info = next_account_info(referrer_accounts)?;
+    let referrer_currency_account = if currency_accounts.is_some() {
+        Some(next_account_info(referrer_accounts)?)
+    } else {
+        None
+    };
+
+    if referrer_account.key() != order.referrer {
+        return err!(Error::InvalidReferrerAccount);
+    }
+
+    let (referrer_key, _) = Pubkey::find_program_address(
+        &[b"referrer".as_ref(), event.event_config.as_ref(), order.referrer.as_ref()],
+        program_id
+    );
+    if referrer_info.key() != referrer_key {
+        return err!(Error::InvalidReferrerAccount);
+    }
+
+    // An unapproved referrer can't hold up the order's settlement
+    if referrer_info.data_is_empty() {
+        return Ok(amount)
+    }
+
+    let mut referrer = Account::<'info, Referrer>::try_from(referrer_info)?;
+
+    pay_from_event(
+        event,
+        referrer_account,
+        referrer_currency_account,
+        currency_accounts,
+        payer_accounts,
+        amount,
+    )?;
+
+    referrer.order_count = referrer.order_count.checked_add(1)
+        .ok_or(Error::OverflowError)?;
+    referrer.total_rewards = referrer.total_rewards.checked_add(amount)
+        .ok_or(Error::OverflowError)?;
+
+    // Accounts from remaining_accounts aren't written back by anchor
+    referrer.exit(program_id)?;
+
+    Ok(0)
+}
+
+/// Accounts that pay for associated token accounts created when paying out of an event
+#[derive(Clone, Copy)]
+pub struct PayerAccounts<'a, 'info> {
+    pub payer: &'a AccountInfo<'info>,
+    pub system_program: &'a AccountInfo<'info>,
+    pub rent: &'a AccountInfo<'info>,
+}
+
+/// Pays `amount` out of the event, creating the associated token account of `to` for SPL events
+pub fn pay_from_event<'info>(
+    event: &Account<'info, Event>,
+    to: &AccountInfo<'info>,
+    to_currency_account: Option<&AccountInfo<'info>>,
+    currency_accounts: Option<EventCurrencyAccounts<'_, 'info>>,
+    payer_accounts: PayerAccounts<'_, 'info>,
+    amount: u64,
+) -> Result<()> {
+    if amount == 0 {
+        return Ok(())
+    }
+
+    let PayerAccounts { payer, system_program, rent } = payer_accounts;
+    match currency_accounts {
+        None => transfer_sol_pda(
+            &mut event.to_account_info(),
+            &mut to.clone(),
+            amount
+        ),
+        Some(accounts) => {
+            let start_time_bytes = &event.start_time.to_le_bytes();
+            let auth_seeds = event.auth_seeds(start_time_bytes);
+
+            transfer(
+                &event.to_account_info(),
+                to,
+                accounts.event_currency_account.into(),
+                to_currency_account,
+                accounts.currency_mint.into(),
+                payer.into(),
+                accounts.ata_program.into(),
+                accounts.token_program.into(),
+                system_program,
+                rent.into(),
+                Some(&auth_seeds),
+                None,
+                amount,
+            )
+        }
+    }
+}
+
+/// Shares of the fee for each recipient and the remainder left for the fee account
+fn get_fee_shares(fee: u64, fee_recipients: &[FeeRecipient]) -> Result<(Vec<u64>, u64)> {
+    let mut remainder = fee;
+    let mut shares = Vec::with_capacity(fee_recipients.len());
+    for recipient in fee_recipients {
+        let share = get_bps_amount(fee, recipient.bps)?;
+        remainder = remainder.checked_sub(share)
+            .ok_or(Error::OverflowError)?;
+        shares.push(share);
+    }
+
+    Ok((shares, remainder))
+}
+
 fn get_earned_amount(
     up_amount: u128,
     down_amount: u128,
@@ -211,7 +602,7 @@ fn get_earned_amount(
         .ok_or(Error::OverflowError)? as u64)
 }
 
-fn get_bps_amount(
+pub(crate) fn get_bps_amount(
     amount: u64,
     bps: u32
 ) -> Result<u64> {
@@ -260,8 +651,15 @@ fn get_amount_to_user(
 
 #[cfg(test)]
 mod tests {
-    use crate::instructions::settle_order::{get_amount_to_user, get_earned_amount, get_bps_amount};
+    use crate::instructions::settle_order::{
+        get_amount_to_user,
+        get_earned_amount,
+        get_bps_amount,
+        get_fee_shares,
+    };
+    use crate::state::FeeRecipient;
     use crate::Outcome;
+    use solana_program::pubkey::Pubkey;
 
     #[test]
     fn earned_amount_up_only() {
@@ -510,4 +908,33 @@ mod tests {
         ).unwrap();
         assert_eq!(13, value);
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn fee_shares_no_recipients() {
+        let (shares, remainder) = get_fee_shares(100, &[]).unwrap();
+        assert_eq!(Vec::<u64>::new(), shares);
+        assert_eq!(100, remainder);
+    }
+
+    #[test]
+    fn fee_shares_split() {
+        let recipients = [
+            FeeRecipient { account: Pubkey::new_unique(), bps: 5000 },
+            FeeRecipient { account: Pubkey::new_unique(), bps: 2500 },
+        ];
+        let (shares, remainder) = get_fee_shares(101, &recipients).unwrap();
+        assert_eq!(vec![50, 25], shares);
+        assert_eq!(26, remainder);
+    }
+
+    #[test]
+    fn fee_shares_full() {
+        let recipients = [
+            FeeRecipient { account: Pubkey::new_unique(), bps: 3000 },
+            FeeRecipient { account: Pubkey::new_unique(), bps: 7000 },
+        ];
+        let (shares, remainder) = get_fee_shares(100, &recipients).unwrap();
+        assert_eq!(vec![30, 70], shares);
+        assert_eq!(0, remainder);
+    }
+}
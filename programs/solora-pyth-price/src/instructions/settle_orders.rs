@@ -0,0 +1,333 @@
+use crate::error::Error;
+use crate::instructions::{
+    get_bps_amount,
+    get_order_payout,
+    pay_fee,
+    pay_from_event,
+    pay_referral,
+    EventCurrencyAccounts,
+    OrderPayout,
+    OrderSettled,
+    PayerAccounts,
+};
+use crate::state::{Event, EventConfig, Order, Outcome};
+use crate::util::{is_native_mint, transfer, transfer_sol_pda};
+use anchor_lang::prelude::*;
+use anchor_spl::token;
+use anchor_spl::token::Burn;
+
+#[derive(Accounts)]
+pub struct SettleOrders<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    #[account(
+        seeds = [
+            b"event_config".as_ref(),
+            event_config.authority.as_ref(),
+            event_config.pyth_feed.as_ref(),
+            event_config.currency_mint.as_ref()
+        ],
+        bump = event_config.bump[0]
+    )]
+    pub event_config: Box<Account<'info, EventConfig>>,
+
+    #[account(
+        mut,
+        seeds = [
+            b"event".as_ref(),
+            event_config.key().as_ref(),
+            &event.start_time.to_le_bytes()
+        ],
+        bump = event.bump[0],
+        constraint = event.outcome != Outcome::Undrawn @ Error::EventNotSettled,
+        constraint = !event.claim_expired @ Error::ClaimPeriodExpired,
+        has_one = fee_account,
+        has_one = event_config
+    )]
+    pub event: Box<Account<'info, Event>>,
+
+    /// CHECK: Safe due to event constraint
+    #[account(mut)]
+    pub fee_account: UncheckedAccount<'info>,
+
+    pub system_program: Program<'info, System>,
+    pub rent: Sysvar<'info, Rent>,
+}
+
+/// Settles and closes many orders of an event at once, paying each order authority the same as
+/// `settle_order` would. Fees, burns and crank rewards are added up and moved once at the end.
+///
+/// For SPL events remaining_accounts start with the currency mint, event currency account, fee
+/// currency account, payer currency account, token program and ATA program. Native events that burn
+/// fees start with the fee burn account instead. They are followed by the event's fee recipients,
+/// each with its currency account for SPL events, and then by an (order, order authority, order
+/// authority currency account) group per order, or an (order, order authority) group per order for
+/// native events. Orders paying a referral reward have the referrer and its referrer account added
+/// to their group, plus the referrer currency account for SPL events. The reward goes to the fee
+/// instead if the referrer account was never created.
+pub fn settle_orders<'info>(ctx: Context<'_, '_, '_, 'info, SettleOrders<'info>>) -> Result<()> {
+    let event_config = &ctx.accounts.event_config;
+    let is_native = is_native_mint(event_config.currency_mint);
+    let event = &ctx.accounts.event;
+    let is_crank_reward_period = event.is_crank_reward_period(Clock::get()?.unix_timestamp);
+    let payer_info = ctx.accounts.payer.to_account_info();
+    let system_program_info = ctx.accounts.system_program.to_account_info();
+    let rent_info = ctx.accounts.rent.to_account_info();
+    let payer_accounts = PayerAccounts {
+        payer: &payer_info,
+        system_program: &system_program_info,
+        rent: &rent_info,
+    };
+    let start_time_bytes = &event.start_time.to_le_bytes();
+    let auth_seeds = event.auth_seeds(start_time_bytes);
+
+    let remaining_accounts = &mut ctx.remaining_accounts.iter();
+    let spl_accounts = if !is_native {
+        Some((
+            next_account_info(remaining_accounts)?,
+            next_account_info(remaining_accounts)?,
+            next_account_info(remaining_accounts)?,
+            next_account_info(remaining_accounts)?,
+            next_account_info(remaining_accounts)?,
+            next_account_info(remaining_accounts)?,
+        ))
+    } else {
+        None
+    };
+
+    if let Some((currency_mint, ..)) = spl_accounts {
+        if event_config.currency_mint != currency_mint.key() {
+            return err!(Error::InvalidMint);
+        }
+    }
+
+    // SOL can't be burned so the burned share goes to the event's fee burn account
+    let fee_burn_bps = if is_native {
+        event.get_native_fee_burn_bps()
+    } else {
+        event.fee_burn_bps
+    };
+    let fee_burn_account = if is_native && fee_burn_bps > 0 {
+        let fee_burn_account = next_account_info(remaining_accounts)?;
+        if fee_burn_account.key() != event.fee_burn_account {
+            return err!(Error::InvalidFeeBurnAccount);
+        }
+
+        Some(fee_burn_account)
+    } else {
+        None
+    };
+
+    let recipient_group_size = if is_native { 1 } else { 2 };
+    let num_recipient_accounts = event.fee_recipients.len() * recipient_group_size;
+    let remaining_accounts = remaining_accounts.as_slice();
+    if remaining_accounts.len() < num_recipient_accounts {
+        return err!(Error::InvalidFeeRecipient);
+    }
+    let (fee_recipient_accounts, order_accounts) = remaining_accounts.split_at(num_recipient_accounts);
+
+    let currency_accounts = spl_accounts.map(|(
+        currency_mint,
+        event_currency_account,
+        _,
+        _,
+        token_program,
+        ata_program
+    )| EventCurrencyAccounts {
+        currency_mint,
+        event_currency_account,
+        token_program,
+        ata_program,
+    });
+
+    let order_accounts = &mut order_accounts.iter();
+    let mut orders: Vec<(Account<'info, Order>, &AccountInfo<'info>)> = Vec::new();
+    let mut total_fee: u64 = 0;
+    let mut total_fee_burned: u64 = 0;
+    let mut total_crank_reward: u64 = 0;
+    while !order_accounts.as_slice().is_empty() {
+        let order = Account::<'info, Order>::try_from(next_account_info(order_accounts)?)?;
+        let authority = next_account_info(order_accounts)?;
+        let authority_currency_account = if is_native {
+            None
+        } else {
+            Some(next_account_info(order_accounts)?)
+        };
+
+        let is_duplicate = orders.iter().any(|(other, _)| other.key() == order.key());
+        if order.event != event.key() || order.authority != authority.key() || is_duplicate {
+            return err!(Error::InvalidOrderAccounts);
+        }
+
+        let OrderPayout {
+            earned_amount,
+            mut fee,
+            crank_reward,
+            mut referral_reward,
+            amount_to_user
+        } = get_order_payout(
+            event,
+            &order,
+            event_config.crank_reward_bps,
+            ctx.accounts.payer.key() != authority.key() && is_crank_reward_period
+        )?;
+
+        pay_from_event(
+            event,
+            authority,
+            authority_currency_account,
+            currency_accounts,
+            payer_accounts,
+            amount_to_user,
+        )?;
+
+        // Referrers differ per order so their rewards can't be added up
+        let unpaid_referral = pay_referral(
+            ctx.program_id,
+            event,
+            &order,
+            currency_accounts,
+            payer_accounts,
+            order_accounts,
+            referral_reward,
+        )?;
+        referral_reward -= unpaid_referral;
+        fee = fee.checked_add(unpaid_referral).ok_or(Error::OverflowError)?;
+
+        // Burn per order so the totals match settling each order on its own
+        let fee_burned = get_bps_amount(fee, fee_burn_bps)?;
+
+        total_fee = total_fee.checked_add(fee)
+            .ok_or(Error::OverflowError)?;
+        total_fee_burned = total_fee_burned.checked_add(fee_burned)
+            .ok_or(Error::OverflowError)?;
+        total_crank_reward = total_crank_reward.checked_add(crank_reward)
+            .ok_or(Error::OverflowError)?;
+
+        emit!(OrderSettled {
+            event_config: event_config.key(),
+            event: event.key(),
+            order: order.key(),
+            authority: authority.key(),
+            payer: ctx.accounts.payer.key(),
+            outcome: order.outcome,
+            event_outcome: event.outcome,
+            amount: order.amount,
+            earned_amount,
+            fee: fee.checked_sub(fee_burned).ok_or(Error::OverflowError)?,
+            fee_burned,
+            crank_reward,
+            referrer: order.referrer,
+            referral_reward,
+            amount_to_user,
+        });
+
+        orders.push((order, authority));
+    }
+
+    if orders.is_empty() {
+        return err!(Error::InvalidOrderAccounts);
+    }
+
+    let total_fee = total_fee.checked_sub(total_fee_burned)
+        .ok_or(Error::OverflowError)?;
+    match spl_accounts {
+        None => {
+            if total_crank_reward > 0 {
+                transfer_sol_pda(
+                    &mut ctx.accounts.event.to_account_info(),
+                    &mut ctx.accounts.payer.to_account_info(),
+                    total_crank_reward
+                )?;
+            }
+
+            if let Some(fee_burn_account) = fee_burn_account {
+                if total_fee_burned > 0 {
+                    transfer_sol_pda(
+                        &mut ctx.accounts.event.to_account_info(),
+                        &mut fee_burn_account.clone(),
+                        total_fee_burned
+                    )?;
+                }
+            }
+
+            pay_fee(
+                event,
+                &ctx.accounts.fee_account.to_account_info(),
+                None,
+                None,
+                payer_accounts,
+                &mut fee_recipient_accounts.iter(),
+                total_fee,
+            )?;
+        }
+        Some((
+            currency_mint,
+            event_currency_account,
+            fee_currency_account,
+            payer_currency_account,
+            token_program,
+            ata_program
+        )) => {
+            if total_crank_reward > 0 {
+                transfer(
+                    &ctx.accounts.event.to_account_info(),
+                    &ctx.accounts.payer.to_account_info(),
+                    event_currency_account.into(),
+                    payer_currency_account.into(),
+                    currency_mint.into(),
+                    Option::from(&ctx.accounts.payer.to_account_info()),
+                    ata_program.into(),
+                    token_program.into(),
+                    &ctx.accounts.system_program.to_account_info(),
+                    Option::from(&ctx.accounts.rent.to_account_info()),
+                    Some(&auth_seeds),
+                    None,
+                    total_crank_reward
+                )?;
+            }
+
+            if total_fee_burned > 0 {
+                token::burn(
+                    CpiContext::new_with_signer(
+                        token_program.to_account_info(),
+                        Burn {
+                            mint: currency_mint.to_account_info(),
+                            authority: ctx.accounts.event.to_account_info(),
+                            from: event_currency_account.to_account_info()
+                        },
+                        &[&auth_seeds]
+                    ),
+                    total_fee_burned
+                )?;
+            }
+
+            pay_fee(
+                event,
+                &ctx.accounts.fee_account.to_account_info(),
+                Some(fee_currency_account),
+                Some(EventCurrencyAccounts {
+                    currency_mint,
+                    event_currency_account,
+                    token_program,
+                    ata_program,
+                }),
+                payer_accounts,
+                &mut fee_recipient_accounts.iter(),
+                total_fee,
+            )?;
+        }
+    }
+
+    // Orders from remaining_accounts aren't closed by anchor, rent goes back to each authority
+    for (order, authority) in &orders {
+        order.close((*authority).clone())?;
+    }
+
+    let event = &mut ctx.accounts.event;
+    event.orders_settled = event.orders_settled.checked_add(orders.len() as u32)
+        .ok_or(Error::OverflowError)?;
+
+    Ok(())
+}
@@ -0,0 +1,145 @@
+use crate::error::Error;
+use crate::state::{Event, EventConfig, Outcome};
+use anchor_lang::prelude::*;
+use anchor_spl::token;
+use anchor_spl::token::{CloseAccount, TokenAccount};
+use crate::util::{is_native_mint, transfer, transfer_sol_pda};
+
+#[derive(Accounts)]
+pub struct SweepEvent<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    #[account(
+        seeds = [
+            b"event_config".as_ref(),
+            event_config.authority.as_ref(),
+            event_config.pyth_feed.as_ref(),
+            event_config.currency_mint.as_ref()
+        ],
+        bump = event_config.bump[0],
+        has_one = authority
+    )]
+    pub event_config: Box<Account<'info, EventConfig>>,
+
+    #[account(
+        mut,
+        seeds = [
+            b"event".as_ref(),
+            event_config.key().as_ref(),
+            &event.start_time.to_le_bytes()
+        ],
+        bump = event.bump[0],
+        has_one = event_config,
+        has_one = fee_account,
+        constraint = event.outcome != Outcome::Undrawn @ Error::EventNotSettled,
+        constraint = !event.claim_expired @ Error::ClaimPeriodExpired,
+    )]
+    pub event: Box<Account<'info, Event>>,
+
+    /// CHECK: Safe due to event constraint
+    #[account(mut)]
+    pub fee_account: UncheckedAccount<'info>,
+
+    pub system_program: Program<'info, System>,
+    pub rent: Sysvar<'info, Rent>,
+}
+
+/// Sends whatever is left in an event to its fee account once the claim period has passed, and
+/// marks the event so orders that were never settled can't be anymore. The event account is kept
+/// so late settles fail with `ClaimPeriodExpired` until unsettled orders are closed for their rent
+/// with `close_expired_order`, after which `close_accounts` can close it.
+///
+/// SPL remaining accounts are the currency mint, event currency account, fee currency account,
+/// token program and ATA program. The emptied event currency account is closed to the authority.
+pub fn sweep_event<'info>(ctx: Context<'_, '_, '_, 'info, SweepEvent<'info>>) -> Result<()> {
+    let event = &ctx.accounts.event;
+
+    let timestamp = Clock::get()?.unix_timestamp;
+    match event.claim_deadline() {
+        Some(deadline) if timestamp >= deadline => {},
+        _ => return err!(Error::ClaimPeriodNotExpired),
+    }
+
+    let start_time_bytes = &event.start_time.to_le_bytes();
+    let auth_seeds = event.auth_seeds(start_time_bytes);
+
+    let swept_amount;
+    if !is_native_mint(ctx.accounts.event_config.currency_mint) {
+        let remaining_accounts = &mut ctx.remaining_accounts.iter();
+        let currency_mint = next_account_info(remaining_accounts)?;
+        let event_currency_account = next_account_info(remaining_accounts)?;
+        let fee_currency_account = next_account_info(remaining_accounts)?;
+        let token_program = next_account_info(remaining_accounts)?;
+        let ata_program = next_account_info(remaining_accounts)?;
+
+        let token_account =
+            Account::<'info, TokenAccount>::try_from(event_currency_account)?;
+        swept_amount = token_account.amount;
+        if swept_amount > 0 {
+            transfer(
+                &event.to_account_info(),
+                &ctx.accounts.fee_account.to_account_info(),
+                event_currency_account.into(),
+                fee_currency_account.into(),
+                currency_mint.into(),
+                Option::from(&ctx.accounts.authority.to_account_info()),
+                ata_program.into(),
+                token_program.into(),
+                &ctx.accounts.system_program.to_account_info(),
+                Option::from(&ctx.accounts.rent.to_account_info()),
+                Some(&auth_seeds),
+                None,
+                swept_amount
+            )?;
+        }
+
+        token::close_account(
+            CpiContext::new_with_signer(
+                token_program.to_account_info(),
+                CloseAccount {
+                    account: token_account.to_account_info(),
+                    destination: ctx.accounts.authority.to_account_info(),
+                    authority: event.to_account_info(),
+                },
+                &[&auth_seeds]
+            )
+        )?;
+    } else {
+        // Keep the event rent exempt so it can stay around as a marker
+        let event_info = event.to_account_info();
+        let rent = Rent::get()?.minimum_balance(event_info.data_len());
+        swept_amount = event_info.lamports().saturating_sub(rent);
+        if swept_amount > 0 {
+            transfer_sol_pda(
+                &mut event.to_account_info(),
+                &mut ctx.accounts.fee_account.to_account_info(),
+                swept_amount
+            )?;
+        }
+    }
+
+    let event = &mut ctx.accounts.event;
+    event.claim_expired = true;
+
+    emit!(EventSwept {
+        event_config: ctx.accounts.event_config.key(),
+        event: event.key(),
+        fee_account: event.fee_account,
+        swept_amount,
+        unsettled_orders: (event.up_count + event.down_count).saturating_sub(event.orders_settled),
+    });
+
+    Ok(())
+}
+
+#[event]
+pub struct EventSwept {
+    pub event_config: Pubkey,
+    pub event: Pubkey,
+    pub fee_account: Pubkey,
+    /// Unclaimed currency sent to the fee account
+    pub swept_amount: u64,
+    /// Orders that can no longer be settled
+    pub unsettled_orders: u32,
+}
@@ -1,5 +1,8 @@
 use anchor_lang::prelude::*;
-use crate::state::{EventConfig};
+use clockwork_sdk::{ID as thread_program_ID, ThreadProgram};
+use crate::state::{EventConfig, EVENT_CONFIG_SIZE, EVENT_CONFIG_VERSION, UpdateEventConfigParams};
+use crate::error::Error;
+use crate::instructions::update_create_thread;
 
 #[derive(Accounts)]
 pub struct UpdateEventConfig<'info> {
@@ -16,19 +19,49 @@ pub struct UpdateEventConfig<'info> {
             event_config.currency_mint.key().as_ref()
         ],
         bump = event_config.bump[0],
-        has_one = authority
+        has_one = authority,
+        constraint = event_config.is_create_thread(&create_thread.key()) @ Error::InvalidCreateThread,
+        realloc = EVENT_CONFIG_SIZE,
+        realloc::payer = authority,
+        realloc::zero = true,
     )]
     pub event_config: Box<Account<'info, EventConfig>>,
+
+    /// CHECK: Allow any account to be the fee account
+    #[account()]
+    pub fee_account: UncheckedAccount<'info>,
+
+    /// CHECK: Safe due to event config constraint. Configs without a create thread can pass any
+    /// writable account, e.g. the authority, which is left untouched.
+    #[account(mut)]
+    pub create_thread: UncheckedAccount<'info>,
+
+    #[account(address = thread_program_ID)]
+    pub clockwork: Program<'info, ThreadProgram>,
+    pub system_program: Program<'info, System>,
 }
 
+/// Changes the settings that are set in `params`. The create thread is rescheduled when the
+/// interval or next event start changes.
 pub fn update_event_config<'info>(
     ctx: Context<'_, '_, '_, 'info, UpdateEventConfig<'info>>,
-    interval_seconds: u32,
-    next_event_start: i64
+    params: UpdateEventConfigParams,
 ) -> Result<()> {
+    let update_schedule = params.changes_schedule();
+
+    // Older configs are grown to the current size by the realloc constraint
     let event_config = &mut ctx.accounts.event_config;
-    event_config.interval_seconds = interval_seconds;
-    event_config.next_event_start = next_event_start;
+    event_config.version = EVENT_CONFIG_VERSION;
+    event_config.fee_account = ctx.accounts.fee_account.key();
+    event_config.update_params(params);
+    event_config.validate()?;
 
-    Ok(())
-}
\ No newline at end of file
+    update_create_thread(
+        event_config,
+        &ctx.accounts.authority.to_account_info(),
+        &ctx.accounts.create_thread.to_account_info(),
+        &ctx.accounts.clockwork.to_account_info(),
+        &ctx.accounts.system_program.to_account_info(),
+        update_schedule,
+    )
+}
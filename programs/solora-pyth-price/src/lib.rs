@@ -1,12 +1,16 @@
 use anchor_lang::prelude::*;
+use clockwork_sdk::state::ThreadResponse;
 use instructions::*;
-use state::Outcome;
+use state::{EventConfigParams, OracleKind, Outcome, UpdateEventConfigParams};
 
 pub mod state;
 pub mod error;
 pub mod instructions;
+mod oracle;
 mod util;
 
+pub use util::change_price_decimals;
+
 declare_id!("SPPq79wtPSBeFvYJbSxS9Pj1JdbQARDWxwJBXyTVcRg");
 
 #[program]
@@ -15,18 +19,38 @@ pub mod solora_pyth_price {
 
     pub fn create_event_config<'info>(
         ctx: Context<'_, '_, '_, 'info, CreateEventConfig<'info>>,
-        interval_seconds: u32,
-        next_event_start: i64
+        params: EventConfigParams,
+        oracle_kind: OracleKind,
+        feed_id: [u8; 32],
     ) -> Result<()> {
-        instructions::create_event_config(ctx, interval_seconds, next_event_start)
+        instructions::create_event_config(ctx, params, oracle_kind, feed_id)
     }
 
     pub fn update_event_config<'info>(
         ctx: Context<'_, '_, '_, 'info, UpdateEventConfig<'info>>,
-        interval_seconds: u32,
-        next_event_start: i64
+        params: UpdateEventConfigParams,
+    ) -> Result<()> {
+        instructions::update_event_config(ctx, params)
+    }
+
+    pub fn migrate_event_config<'info>(
+        ctx: Context<'_, '_, '_, 'info, MigrateEventConfig<'info>>,
+    ) -> Result<()> {
+        instructions::migrate_event_config(ctx)
+    }
+
+    pub fn deposit_vault<'info>(
+        ctx: Context<'_, '_, '_, 'info, DepositVault<'info>>,
+        lamports: u64,
+        amount: u64,
     ) -> Result<()> {
-        instructions::update_event_config(ctx, interval_seconds, next_event_start)
+        instructions::deposit_vault(ctx, lamports, amount)
+    }
+
+    pub fn close_event_config<'info>(
+        ctx: Context<'_, '_, '_, 'info, CloseEventConfig<'info>>,
+    ) -> Result<()> {
+        instructions::close_event_config(ctx)
     }
 
     pub fn create_event<'info>(
@@ -38,6 +62,12 @@ pub mod solora_pyth_price {
         instructions::create_event(ctx, fee_bps, initial_liquidity, fee_burn_bps)
     }
 
+    pub fn create_next_event<'info>(
+        ctx: Context<'_, '_, '_, 'info, CreateNextEvent<'info>>,
+    ) -> Result<ThreadResponse> {
+        instructions::create_next_event(ctx)
+    }
+
     pub fn set_lock_price<'info>(
         ctx: Context<'_, '_, '_, 'info, SetLockPrice<'info>>,
     ) -> Result<()> {
@@ -48,8 +78,23 @@ pub mod solora_pyth_price {
         ctx: Context<'_, '_, '_, 'info, CreateOrder<'info>>,
         outcome: Outcome,
         amount: u64,
+        referrer: Option<Pubkey>,
+    ) -> Result<()> {
+        instructions::create_order(ctx, outcome, amount, referrer)
+    }
+
+    pub fn create_referrer<'info>(
+        ctx: Context<'_, '_, '_, 'info, CreateReferrer<'info>>,
+    ) -> Result<()> {
+        instructions::create_referrer(ctx)
+    }
+
+    pub fn increase_order<'info>(
+        ctx: Context<'_, '_, '_, 'info, IncreaseOrder<'info>>,
+        outcome: Outcome,
+        amount: u64,
     ) -> Result<()> {
-        instructions::create_order(ctx, outcome, amount)
+        instructions::increase_order(ctx, outcome, amount)
     }
 
     pub fn settle_order<'info>(
@@ -58,6 +103,12 @@ pub mod solora_pyth_price {
         instructions::settle_order(ctx)
     }
 
+    pub fn settle_orders<'info>(
+        ctx: Context<'_, '_, '_, 'info, SettleOrders<'info>>,
+    ) -> Result<()> {
+        instructions::settle_orders(ctx)
+    }
+
     pub fn settle_event<'info>(
         ctx: Context<'_, '_, '_, 'info, SettleEvent<'info>>,
     ) -> Result<()> {
@@ -75,4 +126,16 @@ pub mod solora_pyth_price {
     ) -> Result<()> {
         instructions::close_accounts(ctx)
     }
+
+    pub fn sweep_event<'info>(
+        ctx: Context<'_, '_, '_, 'info, SweepEvent<'info>>,
+    ) -> Result<()> {
+        instructions::sweep_event(ctx)
+    }
+
+    pub fn close_expired_order<'info>(
+        ctx: Context<'_, '_, '_, 'info, CloseExpiredOrder<'info>>,
+    ) -> Result<()> {
+        instructions::close_expired_order(ctx)
+    }
 }
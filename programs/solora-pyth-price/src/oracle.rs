@@ -0,0 +1,318 @@
+use anchor_lang::prelude::*;
+use pyth_sdk_solana::Price;
+use pyth_sdk_solana::state::{load_price_account, PriceStatus};
+use solana_program::hash::hash;
+use crate::error::Error;
+use crate::state::{
+    OracleKind,
+    PriceUpdateV2,
+    PYTH_RECEIVER_PROGRAM_ID,
+    SWITCHBOARD_PROGRAM_ID,
+    VerificationLevel,
+};
+
+/// Offsets into a switchboard v2 `AggregatorAccountData`, discriminator included. The account is
+/// packed so fields aren't aligned.
+const SWITCHBOARD_MIN_ORACLE_RESULTS_OFFSET: usize = 8 + 228;
+const SWITCHBOARD_ROUND_OFFSET: usize = 8 + 333;
+/// Offsets of the fields read from an `AggregatorRound`
+const SWITCHBOARD_NUM_SUCCESS_OFFSET: usize = 0;
+const SWITCHBOARD_OPEN_TIMESTAMP_OFFSET: usize = 17;
+const SWITCHBOARD_RESULT_OFFSET: usize = 25;
+const SWITCHBOARD_STD_DEVIATION_OFFSET: usize = 45;
+/// Size of a `SwitchboardDecimal`, an i128 mantissa followed by a u32 scale
+const SWITCHBOARD_DECIMAL_SIZE: usize = 20;
+
+/// Price source for an event config. Prices are returned as pyth `Price`s whatever the oracle, so
+/// price, exponent, confidence and publish time mean the same everywhere.
+pub trait Oracle {
+    /// Latest price without checking how old it is
+    fn latest_price(&self) -> Price;
+
+    /// Prices still held by the account that a publish time can be matched against. Most oracles
+    /// only keep the latest one.
+    fn recent_prices(&self) -> Vec<Price> {
+        vec![self.latest_price()]
+    }
+}
+
+/// Legacy pyth push price account
+pub struct PythPushOracle {
+    latest: Price,
+    previous: Price,
+    is_trading: bool,
+}
+
+impl PythPushOracle {
+    pub fn load(pyth_feed: &AccountInfo) -> Result<Self> {
+        let data = pyth_feed.try_borrow_data()?;
+        let price_account = load_price_account(&data)
+            .map_err(|_| Error::InvalidOracle)?;
+
+        Ok(Self {
+            latest: price_account.to_price_feed(pyth_feed.key).get_price_unchecked(),
+            previous: Price {
+                price: price_account.prev_price,
+                conf: price_account.prev_conf,
+                expo: price_account.expo,
+                publish_time: price_account.prev_timestamp,
+            },
+            is_trading: price_account.agg.status == PriceStatus::Trading,
+        })
+    }
+}
+
+impl Oracle for PythPushOracle {
+    fn latest_price(&self) -> Price {
+        self.latest
+    }
+
+    /// The account keeps the previous aggregate next to the current one, which is only usable
+    /// while trading
+    fn recent_prices(&self) -> Vec<Price> {
+        let mut prices = vec![self.previous];
+        if self.is_trading {
+            prices.push(self.latest);
+        }
+
+        prices
+    }
+}
+
+/// Pyth pull oracle price update account
+pub struct PythPullOracle {
+    price: Price,
+}
+
+impl PythPullOracle {
+    /// Only fully verified updates for `feed_id` posted by the pyth receiver program are accepted
+    pub fn load(price_update: &AccountInfo, feed_id: &[u8; 32]) -> Result<Self> {
+        if *price_update.owner != PYTH_RECEIVER_PROGRAM_ID {
+            return err!(Error::InvalidOracle);
+        }
+
+        let data = price_update.try_borrow_data()?;
+        let discriminator = &hash(b"account:PriceUpdateV2").to_bytes()[..8];
+        if data.len() < 8 || &data[..8] != discriminator {
+            return err!(Error::InvalidOracle);
+        }
+
+        let price_update = PriceUpdateV2::deserialize(&mut &data[8..])
+            .map_err(|_| Error::InvalidOracle)?;
+        if price_update.verification_level != VerificationLevel::Full {
+            return err!(Error::InvalidOracle);
+        }
+
+        let message = price_update.price_message;
+        if &message.feed_id != feed_id {
+            return err!(Error::FeedIdMismatch);
+        }
+
+        Ok(Self {
+            price: Price {
+                price: message.price,
+                conf: message.conf,
+                expo: message.exponent,
+                publish_time: message.publish_time,
+            },
+        })
+    }
+}
+
+impl Oracle for PythPullOracle {
+    fn latest_price(&self) -> Price {
+        self.price
+    }
+}
+
+/// Switchboard v2 aggregator. The latest confirmed round is used with its standard deviation as
+/// the confidence and its open time as the publish time. The exponent follows the round's scale,
+/// which can change between rounds, so prices must be brought to the event's decimals with
+/// `get_price_with_decimal_change` before they're compared.
+pub struct SwitchboardOracle {
+    price: Price,
+}
+
+impl SwitchboardOracle {
+    pub fn load(aggregator: &AccountInfo) -> Result<Self> {
+        if *aggregator.owner != SWITCHBOARD_PROGRAM_ID {
+            return err!(Error::InvalidOracle);
+        }
+
+        let data = aggregator.try_borrow_data()?;
+        let discriminator = &hash(b"account:AggregatorAccountData").to_bytes()[..8];
+        let round_end = SWITCHBOARD_ROUND_OFFSET + SWITCHBOARD_STD_DEVIATION_OFFSET + SWITCHBOARD_DECIMAL_SIZE;
+        if data.len() < round_end || &data[..8] != discriminator {
+            return err!(Error::InvalidOracle);
+        }
+
+        // Rounds with fewer responses than the aggregator asks for can be moved by a single oracle
+        let min_oracle_results =
+            u32::from_le_bytes(read_bytes(&data, SWITCHBOARD_MIN_ORACLE_RESULTS_OFFSET));
+        let round = &data[SWITCHBOARD_ROUND_OFFSET..round_end];
+        let num_success = u32::from_le_bytes(read_bytes(round, SWITCHBOARD_NUM_SUCCESS_OFFSET));
+        if num_success == 0 || num_success < min_oracle_results {
+            return err!(Error::InvalidPrice);
+        }
+
+        let (mantissa, scale) = read_switchboard_decimal(round, SWITCHBOARD_RESULT_OFFSET);
+        let (std_mantissa, std_scale) = read_switchboard_decimal(round, SWITCHBOARD_STD_DEVIATION_OFFSET);
+        let publish_time = i64::from_le_bytes(read_bytes(round, SWITCHBOARD_OPEN_TIMESTAMP_OFFSET));
+
+        let price = get_switchboard_price(mantissa, scale, std_mantissa, std_scale, publish_time)
+            .ok_or(Error::InvalidPrice)?;
+
+        Ok(Self { price })
+    }
+}
+
+impl Oracle for SwitchboardOracle {
+    fn latest_price(&self) -> Price {
+        self.price
+    }
+}
+
+/// Loads the oracle of a config's `oracle_kind` from its oracle account
+pub fn load_oracle(
+    oracle_kind: OracleKind,
+    feed_id: &[u8; 32],
+    oracle_account: &AccountInfo,
+) -> Result<Box<dyn Oracle>> {
+    Ok(match oracle_kind {
+        OracleKind::PythPush => Box::new(PythPushOracle::load(oracle_account)?),
+        OracleKind::PythPull => Box::new(PythPullOracle::load(oracle_account, feed_id)?),
+        OracleKind::Switchboard => Box::new(SwitchboardOracle::load(oracle_account)?),
+    })
+}
+
+/// Price to use for an event's lock or settle at `target_time`. With a publish time tolerance the
+/// published price nearest `target_time` is used as long as it's within the tolerance, otherwise
+/// the current price is used as long as it's no older than the max price age.
+pub fn get_event_price(
+    oracle_kind: OracleKind,
+    feed_id: &[u8; 32],
+    oracle_account: &AccountInfo,
+    timestamp: i64,
+    target_time: i64,
+    publish_time_tolerance: u32,
+    max_price_age: u64,
+) -> Result<Option<Price>> {
+    let oracle = load_oracle(oracle_kind, feed_id, oracle_account)?;
+    if publish_time_tolerance == 0 {
+        let price = oracle.latest_price();
+        let is_fresh = timestamp.saturating_sub(price.publish_time) <= max_price_age as i64;
+        return Ok(if is_fresh { Some(price) } else { None })
+    }
+
+    Ok(get_nearest_price(&oracle.recent_prices(), target_time, publish_time_tolerance))
+}
+
+/// Price published nearest to `target_time` if within `tolerance` seconds of it
+fn get_nearest_price(prices: &[Price], target_time: i64, tolerance: u32) -> Option<Price> {
+    prices.iter()
+        .filter(|price| price.publish_time.abs_diff(target_time) <= tolerance as u64)
+        .min_by_key(|price| price.publish_time.abs_diff(target_time))
+        .copied()
+}
+
+fn read_bytes<const N: usize>(data: &[u8], offset: usize) -> [u8; N] {
+    let mut bytes = [0u8; N];
+    bytes.copy_from_slice(&data[offset..offset + N]);
+    bytes
+}
+
+fn read_switchboard_decimal(data: &[u8], offset: usize) -> (i128, u32) {
+    (
+        i128::from_le_bytes(read_bytes(data, offset)),
+        u32::from_le_bytes(read_bytes(data, offset + 16)),
+    )
+}
+
+/// Switchboard decimals as a pyth price. Decimals are dropped until the result fits in an i64, and
+/// the standard deviation is brought to the same scale as the result.
+fn get_switchboard_price(
+    mantissa: i128,
+    scale: u32,
+    std_mantissa: i128,
+    std_scale: u32,
+    publish_time: i64,
+) -> Option<Price> {
+    let mut mantissa = mantissa;
+    let mut scale = scale;
+    while i64::try_from(mantissa).is_err() {
+        if scale == 0 {
+            return None
+        }
+
+        mantissa /= 10;
+        scale -= 1;
+    }
+
+    let conf = if std_scale >= scale {
+        // Anything past u128 precision rounds to 0
+        10_u128.checked_pow(std_scale - scale)
+            .map_or(0, |power| std_mantissa.unsigned_abs() / power)
+    } else {
+        std_mantissa.unsigned_abs().checked_mul(10_u128.checked_pow(scale - std_scale)?)?
+    };
+
+    Some(Price {
+        price: mantissa as i64,
+        conf: u64::try_from(conf).ok()?,
+        expo: -(scale as i32),
+        publish_time,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::oracle::{get_nearest_price, get_switchboard_price};
+    use pyth_sdk_solana::Price;
+
+    fn price_at(publish_time: i64) -> Price {
+        Price { price: 100, conf: 1, expo: -2, publish_time }
+    }
+
+    #[test]
+    fn nearest_price_within_tolerance() {
+        let prices = [price_at(98), price_at(103)];
+        assert_eq!(Some(price_at(98)), get_nearest_price(&prices, 100, 5));
+        assert_eq!(Some(price_at(103)), get_nearest_price(&prices, 102, 5));
+    }
+
+    #[test]
+    fn nearest_price_outside_tolerance() {
+        let prices = [price_at(90), price_at(110)];
+        assert_eq!(None, get_nearest_price(&prices, 100, 5));
+    }
+
+    #[test]
+    fn switchboard_price() {
+        let price = get_switchboard_price(2_315_000, 4, 15, 4, 100).unwrap();
+        assert_eq!(Price { price: 2_315_000, conf: 15, expo: -4, publish_time: 100 }, price);
+    }
+
+    #[test]
+    fn switchboard_price_std_deviation_rescaled() {
+        let price = get_switchboard_price(2_315_000, 4, 15_000, 7, 100).unwrap();
+        assert_eq!(15, price.conf);
+
+        let price = get_switchboard_price(2_315_000, 4, 15, 2, 100).unwrap();
+        assert_eq!(1_500, price.conf);
+    }
+
+    #[test]
+    fn switchboard_price_drops_decimals() {
+        // 23.15 with 28 decimals doesn't fit in an i64
+        let mantissa = 2_315 * 10_i128.pow(26);
+        let price = get_switchboard_price(mantissa, 28, 10_i128.pow(26), 28, 100).unwrap();
+        assert_eq!(2_315 * 10_i64.pow(15), price.price);
+        assert_eq!(-17, price.expo);
+        assert_eq!(10_u64.pow(15), price.conf);
+    }
+
+    #[test]
+    fn switchboard_price_too_large() {
+        assert_eq!(None, get_switchboard_price(i128::MAX, 0, 0, 0, 100));
+    }
+}
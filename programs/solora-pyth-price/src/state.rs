@@ -2,10 +2,14 @@ mod event;
 mod outcome;
 mod order;
 mod event_config;
+mod referrer;
+mod oracle;
 
 pub use event::*;
 pub use outcome::*;
 pub use order::*;
 pub use event_config::*;
+pub use referrer::*;
+pub use oracle::*;
 
 pub const MAX_PRICE_DECIMALS: u8 = 4;
\ No newline at end of file
@@ -1,11 +1,21 @@
 use anchor_lang::prelude::*;
 use solana_program::pubkey::Pubkey;
+use crate::state::event_config::{FEE_RECIPIENT_SIZE, FeeRecipient, MAX_FEE_RECIPIENTS};
 use crate::state::outcome::Outcome;
 
 pub const EVENT_VERSION: u8 = 1;
 
+/// Used for events created before the grace period and price age were configurable
+pub const DEFAULT_GRACE_PERIOD: u32 = 15;
+pub const DEFAULT_MAX_PRICE_AGE: u32 = 30;
+
+/// Seconds after settlement that order authorities have to settle their own orders before someone
+/// else settling them earns the crank reward
+pub const CRANK_REWARD_DELAY: i64 = 60 * 60;
+
 pub const EVENT_SIZE: usize =
-	8 + 1 + 1 + 32 + 32 + 32 + 32 + 4 + 8 + 8 + 4 + 8 + 8 + 2 + 16 + 16 + 4 + 4 + 1 + 4 + 4 + 252;
+	8 + 1 + 1 + 32 + 32 + 32 + 32 + 4 + 8 + 8 + 4 + 8 + 8 + 2 + 16 + 16 + 4 + 4 + 1 + 4 + 4 + 4 + 1 +
+	(4 + MAX_FEE_RECIPIENTS * FEE_RECIPIENT_SIZE) + 4 + 32 + 4 + 1 + 8 + 8 + 4 + 4 + 4 + 8 + 8 + 14;
 
 #[account]
 pub struct Event {
@@ -41,13 +51,83 @@ pub struct Event {
 	pub down_count: u32,
 	/// Number of decimals to consider for price changes
 	pub price_decimals: u8,
-	/// Number of orders settled. Once it reaches the up_count + down_count it's safe to close the event
+	/// Number of orders settled, or closed after the event was swept. Once it reaches the up_count +
+	/// down_count it's safe to close the event
 	pub orders_settled: u32,
 	/// Amount in bps to burn from the fees received
 	pub fee_burn_bps: u32,
+	/// Seconds after settlement that orders can be settled before unclaimed funds can be swept
+	pub claim_period: u32,
+	/// Unclaimed funds were swept to the fee account so remaining orders can no longer be settled
+	pub claim_expired: bool,
+	/// Accounts that share the fee, copied from the config when the event is created
+	pub fee_recipients: Vec<FeeRecipient>,
+	/// Share of the fee in bps paid to the referrer of an order
+	pub referral_fee_bps: u32,
+	/// Account receiving the burned share of native fees
+	pub fee_burn_account: Pubkey,
+	/// Widest pyth confidence interval in bps of the price accepted when locking or settling
+	pub max_conf_bps: u32,
+	/// Resolve as same when the price moved less than the confidence interval
+	pub same_band: bool,
+	/// Confidence interval of the lock and settle prices with the same decimals as the prices
+	pub lock_conf: u64,
+	pub settle_conf: u64,
+	/// Seconds after the lock/settle time that the price can still be set
+	pub grace_period: u32,
+	/// Oldest pyth price in seconds accepted when locking or settling
+	pub max_price_age: u32,
+	/// Furthest in seconds a pyth publish time can be from the lock/settle time, 0 to use the
+	/// current price instead
+	pub publish_time_tolerance: u32,
+	/// Pyth publish times of the lock and settle prices
+	pub lock_publish_time: i64,
+	pub settle_publish_time: i64,
 }
 
 impl Event {
+	/// Settling someone else's order only pays the crank reward once the authority had time to
+	/// settle it. Owners settling their own orders from a second wallet would otherwise collect it,
+	/// now they would have to leave their payout unclaimed for the whole delay.
+	pub fn is_crank_reward_period(&self, timestamp: i64) -> bool {
+		timestamp >= self.lock_time + self.wait_period as i64 + CRANK_REWARD_DELAY
+	}
+
+	pub fn get_grace_period(&self) -> i64 {
+		if self.grace_period == 0 {
+			return DEFAULT_GRACE_PERIOD as i64
+		}
+
+		self.grace_period as i64
+	}
+
+	pub fn get_max_price_age(&self) -> u64 {
+		if self.max_price_age == 0 {
+			return DEFAULT_MAX_PRICE_AGE as u64
+		}
+
+		self.max_price_age as u64
+	}
+
+	/// Share of native fees sent to the fee burn account. Events created before native fees could be
+	/// burned have no fee burn account and keep the whole fee like before.
+	pub fn get_native_fee_burn_bps(&self) -> u32 {
+		if self.fee_burn_account == Pubkey::default() {
+			return 0
+		}
+
+		self.fee_burn_bps
+	}
+
+	/// Timestamp after which unclaimed funds can be swept, if ever
+	pub fn claim_deadline(&self) -> Option<i64> {
+		if self.claim_period == 0 {
+			return None
+		}
+
+		Some(self.lock_time + self.wait_period as i64 + self.claim_period as i64)
+	}
+
 	pub fn auth_seeds<'a>(&'a self, start_time_bytes: &'a [u8]) -> [&'a[u8]; 4] {
 		[
 			b"event".as_ref(),
@@ -1,10 +1,94 @@
 use anchor_lang::prelude::*;
 use solana_program::pubkey::Pubkey;
+use crate::state::oracle::OracleKind;
+use crate::error::Error;
+use crate::util::{get_interval_schedule, is_native_mint};
 
-pub const EVENT_CONFIG_VERSION: u8 = 1;
+/// Configs from before version 3 were allocated with less space, see `migrate_event_config`
+pub const EVENT_CONFIG_VERSION: u8 = 3;
+
+pub const MAX_FEE_RECIPIENTS: usize = 4;
+
+pub const FEE_RECIPIENT_SIZE: usize = 32 + 4;
 
 pub const EVENT_CONFIG_SIZE: usize =
-	8 + 1 + 1 + 32 + 32 + 32 + 4 + 8 + 256;
+	8 + 1 + 1 + 32 + 32 + 32 + 4 + 8 + 32 + 32 + 4 + 4 + 8 + 4 + 4 +
+	(4 + MAX_FEE_RECIPIENTS * FEE_RECIPIENT_SIZE) + 4 + 32 + 4 + 1 + 4 + 4 + 4 + 1 + 32 + 3;
+
+#[derive(
+	AnchorSerialize,
+	AnchorDeserialize,
+	Clone,
+	Copy,
+	PartialEq,
+	Debug
+)]
+pub struct FeeRecipient {
+	/// Account receiving the share, SPL shares go to its associated token account
+	pub account: Pubkey,
+	/// Share of the fee in bps after burning, the rest goes to the fee account
+	pub bps: u32,
+}
+
+/// Settings of a config passed to `create_event_config`, see `EventConfig` for what they do
+#[derive(
+	AnchorSerialize,
+	AnchorDeserialize,
+	Clone,
+	PartialEq,
+	Debug
+)]
+pub struct EventConfigParams {
+	pub interval_seconds: u32,
+	pub next_event_start: i64,
+	pub fee_bps: u32,
+	pub fee_burn_bps: u32,
+	pub initial_liquidity: u64,
+	pub crank_reward_bps: u32,
+	pub claim_period_seconds: u32,
+	pub fee_recipients: Vec<FeeRecipient>,
+	pub referral_fee_bps: u32,
+	pub fee_burn_account: Pubkey,
+	pub max_conf_bps: u32,
+	pub same_band: bool,
+	pub grace_period_seconds: u32,
+	pub max_price_age_seconds: u32,
+	pub publish_time_tolerance_seconds: u32,
+}
+
+/// Settings passed to `update_event_config`, only the ones that are set are changed
+#[derive(
+	AnchorSerialize,
+	AnchorDeserialize,
+	Clone,
+	Default,
+	PartialEq,
+	Debug
+)]
+pub struct UpdateEventConfigParams {
+	pub interval_seconds: Option<u32>,
+	pub next_event_start: Option<i64>,
+	pub fee_bps: Option<u32>,
+	pub fee_burn_bps: Option<u32>,
+	pub initial_liquidity: Option<u64>,
+	pub crank_reward_bps: Option<u32>,
+	pub claim_period_seconds: Option<u32>,
+	pub fee_recipients: Option<Vec<FeeRecipient>>,
+	pub referral_fee_bps: Option<u32>,
+	pub fee_burn_account: Option<Pubkey>,
+	pub max_conf_bps: Option<u32>,
+	pub same_band: Option<bool>,
+	pub grace_period_seconds: Option<u32>,
+	pub max_price_age_seconds: Option<u32>,
+	pub publish_time_tolerance_seconds: Option<u32>,
+}
+
+impl UpdateEventConfigParams {
+	/// Whether the create thread has to be rescheduled
+	pub fn changes_schedule(&self) -> bool {
+		self.interval_seconds.is_some() || self.next_event_start.is_some()
+	}
+}
 
 #[account]
 pub struct EventConfig {
@@ -21,11 +105,189 @@ pub struct EventConfig {
 	pub interval_seconds: u32,
 	/// Unix timestamp of the next time an event should start for this config
 	pub next_event_start: i64,
+	/// Clockwork thread that creates the next event every interval
+	pub create_thread: Pubkey,
+	/// Account to receive fees for events created by the thread
+	pub fee_account: Pubkey,
+	/// Fee rate in bps for events created by the thread
+	pub fee_bps: u32,
+	/// Amount in bps to burn from the fees received for events created by the thread
+	pub fee_burn_bps: u32,
+	/// Liquidity moved from the vault into each event created by the thread
+	pub initial_liquidity: u64,
+	/// Share of the fee in bps paid to whoever settles an order on behalf of its authority, once
+	/// `CRANK_REWARD_DELAY` has passed since settlement
+	pub crank_reward_bps: u32,
+	/// Seconds after settlement that orders can be settled before unclaimed funds can be swept,
+	/// 0 to never allow sweeping
+	pub claim_period_seconds: u32,
+	/// Accounts that share the fees of events created for this config
+	pub fee_recipients: Vec<FeeRecipient>,
+	/// Share of the fee in bps paid to the referrer of an order. Only referrers approved by the
+	/// config authority with `create_referrer` are paid, otherwise the share stays in the fee.
+	pub referral_fee_bps: u32,
+	/// Account receiving the burned share of native fees since SOL can't be burned, e.g. the
+	/// incinerator or a buy back vault
+	pub fee_burn_account: Pubkey,
+	/// Widest pyth confidence interval in bps of the price accepted when locking or settling, 0 to
+	/// accept any
+	pub max_conf_bps: u32,
+	/// Resolve as same when the price moved less than the confidence interval
+	pub same_band: bool,
+	/// Seconds after the lock/settle time that the price can still be set before the event is
+	/// made invalid
+	pub grace_period_seconds: u32,
+	/// Oldest pyth price in seconds accepted when locking or settling
+	pub max_price_age_seconds: u32,
+	/// Furthest in seconds a pyth publish time can be from the lock/settle time, 0 to use the
+	/// current price instead
+	pub publish_time_tolerance_seconds: u32,
+	/// Kind of oracle account `pyth_feed` is, which may not be a pyth account
+	pub oracle_kind: OracleKind,
+	/// Pyth feed id price updates must be for when using the pull oracle
+	pub feed_id: [u8; 32],
 }
 
 impl EventConfig {
+	pub fn set_params(&mut self, params: EventConfigParams) {
+		self.interval_seconds = params.interval_seconds;
+		self.next_event_start = params.next_event_start;
+		self.fee_bps = params.fee_bps;
+		self.fee_burn_bps = params.fee_burn_bps;
+		self.initial_liquidity = params.initial_liquidity;
+		self.crank_reward_bps = params.crank_reward_bps;
+		self.claim_period_seconds = params.claim_period_seconds;
+		self.fee_recipients = params.fee_recipients;
+		self.referral_fee_bps = params.referral_fee_bps;
+		self.fee_burn_account = params.fee_burn_account;
+		self.max_conf_bps = params.max_conf_bps;
+		self.same_band = params.same_band;
+		self.grace_period_seconds = params.grace_period_seconds;
+		self.max_price_age_seconds = params.max_price_age_seconds;
+		self.publish_time_tolerance_seconds = params.publish_time_tolerance_seconds;
+	}
+
+	pub fn update_params(&mut self, params: UpdateEventConfigParams) {
+		let UpdateEventConfigParams {
+			interval_seconds,
+			next_event_start,
+			fee_bps,
+			fee_burn_bps,
+			initial_liquidity,
+			crank_reward_bps,
+			claim_period_seconds,
+			fee_recipients,
+			referral_fee_bps,
+			fee_burn_account,
+			max_conf_bps,
+			same_band,
+			grace_period_seconds,
+			max_price_age_seconds,
+			publish_time_tolerance_seconds,
+		} = params;
+
+		self.set_params(EventConfigParams {
+			interval_seconds: interval_seconds.unwrap_or(self.interval_seconds),
+			next_event_start: next_event_start.unwrap_or(self.next_event_start),
+			fee_bps: fee_bps.unwrap_or(self.fee_bps),
+			fee_burn_bps: fee_burn_bps.unwrap_or(self.fee_burn_bps),
+			initial_liquidity: initial_liquidity.unwrap_or(self.initial_liquidity),
+			crank_reward_bps: crank_reward_bps.unwrap_or(self.crank_reward_bps),
+			claim_period_seconds: claim_period_seconds.unwrap_or(self.claim_period_seconds),
+			fee_recipients: fee_recipients.unwrap_or_else(|| self.fee_recipients.clone()),
+			referral_fee_bps: referral_fee_bps.unwrap_or(self.referral_fee_bps),
+			fee_burn_account: fee_burn_account.unwrap_or(self.fee_burn_account),
+			max_conf_bps: max_conf_bps.unwrap_or(self.max_conf_bps),
+			same_band: same_band.unwrap_or(self.same_band),
+			grace_period_seconds: grace_period_seconds.unwrap_or(self.grace_period_seconds),
+			max_price_age_seconds: max_price_age_seconds.unwrap_or(self.max_price_age_seconds),
+			publish_time_tolerance_seconds: publish_time_tolerance_seconds
+				.unwrap_or(self.publish_time_tolerance_seconds),
+		});
+	}
+
+	/// Checks the settings after they are created or updated
+	pub fn validate(&self) -> Result<()> {
+		if self.fee_bps > 10_000 {
+			return err!(Error::InvalidFee)
+		}
+
+		if self.fee_burn_bps > 10_000 {
+			return err!(Error::InvalidFeeBurn)
+		}
+
+		if self.max_conf_bps > 10_000 {
+			return err!(Error::InvalidMaxConfidence)
+		}
+
+		// Also bounds the periods below since they have to fit in an interval. Legacy configs
+		// without a create thread are checked too so they can't be given an unbounded interval.
+		if get_interval_schedule(self.next_event_start, self.interval_seconds).is_none() {
+			return err!(Error::InvalidInterval)
+		}
+
+		// Prices have to be set before the next event locks
+		if self.grace_period_seconds == 0 || self.grace_period_seconds >= self.interval_seconds {
+			return err!(Error::InvalidGracePeriod)
+		}
+
+		if self.max_price_age_seconds == 0 || self.max_price_age_seconds > self.interval_seconds {
+			return err!(Error::InvalidMaxPriceAge)
+		}
+
+		if self.publish_time_tolerance_seconds >= self.interval_seconds {
+			return err!(Error::InvalidPublishTimeTolerance)
+		}
+
+		// Native fees are burned by sending them to the fee burn account
+		let is_native = is_native_mint(self.currency_mint);
+		if is_native && self.fee_burn_bps > 0 && self.fee_burn_account == Pubkey::default() {
+			return err!(Error::InvalidFeeBurn)
+		}
+
+		if self.crank_reward_bps > 10_000 {
+			return err!(Error::InvalidCrankReward)
+		}
+
+		if !EventConfig::is_valid_fee_split(&self.fee_recipients) {
+			return err!(Error::InvalidFeeRecipients)
+		}
+
+		// Both rewards come out of the fee
+		if self.referral_fee_bps > 10_000 - self.crank_reward_bps {
+			return err!(Error::InvalidReferralFee)
+		}
+
+		Ok(())
+	}
+
+	/// Up to `MAX_FEE_RECIPIENTS` distinct recipients with shares adding up to at most 100%
+	pub fn is_valid_fee_split(fee_recipients: &[FeeRecipient]) -> bool {
+		if fee_recipients.len() > MAX_FEE_RECIPIENTS {
+			return false
+		}
+
+		let total_bps: u64 = fee_recipients.iter().map(|recipient| recipient.bps as u64).sum();
+		let has_duplicates = fee_recipients.iter().enumerate().any(|(i, recipient)| {
+			fee_recipients[..i].iter().any(|other| other.account == recipient.account)
+		});
+
+		total_bps <= 10_000 && !has_duplicates
+	}
+
+	/// Configs from before the create thread have the default pubkey and only create events
+	/// manually
+	pub fn has_create_thread(&self) -> bool {
+		self.create_thread != Pubkey::default()
+	}
+
+	/// Configs without a create thread accept any account in its place
+	pub fn is_create_thread(&self, key: &Pubkey) -> bool {
+		!self.has_create_thread() || self.create_thread == *key
+	}
+
 	/// Seeds are unique to authority/pyth feed/currency mint combinations
-	pub fn auth_seeds<'a>(&'a self) -> [&'a[u8]; 5] {
+	pub fn auth_seeds(&self) -> [&[u8]; 5] {
 		[
 			b"event_config".as_ref(),
 			self.authority.as_ref(),
@@ -34,4 +296,81 @@ impl EventConfig {
 			self.bump.as_ref()
 		]
 	}
-}
\ No newline at end of file
+}
+#[cfg(test)]
+mod tests {
+	use anchor_lang::prelude::*;
+	use crate::error::Error;
+	use crate::state::{EventConfig, OracleKind, UpdateEventConfigParams};
+
+	/// Config without a create thread like the ones created before it existed
+	fn legacy_config() -> EventConfig {
+		EventConfig {
+			bump: [255],
+			version: 1,
+			authority: Pubkey::new_unique(),
+			pyth_feed: Pubkey::new_unique(),
+			currency_mint: Pubkey::new_unique(),
+			interval_seconds: 300,
+			next_event_start: 1_672_531_200,
+			create_thread: Pubkey::default(),
+			fee_account: Pubkey::new_unique(),
+			fee_bps: 300,
+			fee_burn_bps: 0,
+			initial_liquidity: 0,
+			crank_reward_bps: 0,
+			claim_period_seconds: 0,
+			fee_recipients: vec![],
+			referral_fee_bps: 0,
+			fee_burn_account: Pubkey::default(),
+			max_conf_bps: 0,
+			same_band: false,
+			grace_period_seconds: 60,
+			max_price_age_seconds: 60,
+			publish_time_tolerance_seconds: 10,
+			oracle_kind: OracleKind::PythPush,
+			feed_id: [0; 32],
+		}
+	}
+
+	#[test]
+	fn validate_legacy_config() {
+		assert!(legacy_config().validate().is_ok());
+	}
+
+	#[test]
+	fn update_unschedulable_interval() {
+		let mut config = legacy_config();
+		config.update_params(UpdateEventConfigParams {
+			interval_seconds: Some(172_800),
+			grace_period_seconds: Some(100_000),
+			max_price_age_seconds: Some(100_000),
+			..Default::default()
+		});
+		assert_eq!(config.validate().unwrap_err(), error!(Error::InvalidInterval));
+	}
+
+	#[test]
+	fn update_periods_longer_than_interval() {
+		let mut config = legacy_config();
+		config.update_params(UpdateEventConfigParams {
+			grace_period_seconds: Some(u32::MAX),
+			..Default::default()
+		});
+		assert_eq!(config.validate().unwrap_err(), error!(Error::InvalidGracePeriod));
+
+		let mut config = legacy_config();
+		config.update_params(UpdateEventConfigParams {
+			max_price_age_seconds: Some(301),
+			..Default::default()
+		});
+		assert_eq!(config.validate().unwrap_err(), error!(Error::InvalidMaxPriceAge));
+
+		let mut config = legacy_config();
+		config.update_params(UpdateEventConfigParams {
+			publish_time_tolerance_seconds: Some(300),
+			..Default::default()
+		});
+		assert_eq!(config.validate().unwrap_err(), error!(Error::InvalidPublishTimeTolerance));
+	}
+}
@@ -0,0 +1,55 @@
+use anchor_lang::prelude::*;
+use solana_program::{pubkey, pubkey::Pubkey};
+
+/// Program that posts verified pyth pull oracle price updates
+pub const PYTH_RECEIVER_PROGRAM_ID: Pubkey = pubkey!("rec5EKMGg6MxZYaMdyBfgwp4d5rB9T1VQH5pJv5LtFJ");
+
+/// Switchboard v2 program that owns aggregator accounts
+pub const SWITCHBOARD_PROGRAM_ID: Pubkey = pubkey!("SW1TCH7qEPTdLsDHRgPuMQjbQxKdH2aBStViMFnt64f");
+
+#[derive(
+	AnchorSerialize,
+	AnchorDeserialize,
+	Clone,
+	Copy,
+	PartialEq,
+	Debug
+)]
+pub enum OracleKind {
+	/// Legacy pyth push price account
+	PythPush,
+	/// Pyth pull oracle price update account verified against the config's feed id. Like push feeds
+	/// the account is fixed, so it should be a sponsored feed account that is updated in place.
+	PythPull,
+	/// Switchboard v2 aggregator account, for assets pyth doesn't cover
+	Switchboard,
+}
+
+/// Layout of the pyth receiver's `PriceUpdateV2` account after the discriminator
+#[derive(AnchorDeserialize)]
+pub struct PriceUpdateV2 {
+	pub write_authority: Pubkey,
+	pub verification_level: VerificationLevel,
+	pub price_message: PriceFeedMessage,
+	pub posted_slot: u64,
+}
+
+#[derive(AnchorDeserialize, PartialEq)]
+pub enum VerificationLevel {
+	Partial {
+		num_signatures: u8
+	},
+	Full,
+}
+
+#[derive(AnchorDeserialize)]
+pub struct PriceFeedMessage {
+	pub feed_id: [u8; 32],
+	pub price: i64,
+	pub conf: u64,
+	pub exponent: i32,
+	pub publish_time: i64,
+	pub prev_publish_time: i64,
+	pub ema_price: i64,
+	pub ema_conf: u64,
+}
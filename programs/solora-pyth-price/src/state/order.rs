@@ -2,6 +2,8 @@ use anchor_lang::prelude::*;
 use solana_program::pubkey::Pubkey;
 use crate::Outcome;
 
+/// Left with unused space after the original layout, `referrer` fits in it so existing orders
+/// read it as the default pubkey
 pub const ORDER_SIZE: usize = 8 + 1 + 1 + 4 + 32 + 32 + 1 + 8 + 32 + 4 + 8 + 8 + 4;
 
 #[account]
@@ -12,7 +14,9 @@ pub struct Order {
 	pub authority: Pubkey,
 	pub event: Pubkey,
 	pub outcome: Outcome,
-	pub amount: u64
+	pub amount: u64,
+	/// Account that referred the order or the default pubkey if none
+	pub referrer: Pubkey,
 }
 
 impl Order {
@@ -20,12 +24,77 @@ impl Order {
 		ORDER_SIZE
 	}
 
-	pub fn auth_seeds<'a>(&'a self) -> [&'a [u8]; 4] {
+	pub fn has_referrer(&self) -> bool {
+		self.referrer != Pubkey::default()
+	}
+
+	/// Orders are per outcome so a user can hold both sides of an event
+	pub fn auth_seeds<'a>(&'a self, outcome_bytes: &'a [u8]) -> [&'a [u8]; 5] {
 		[
 			b"order".as_ref(),
 			self.event.as_ref(),
 			self.authority.as_ref(),
+			outcome_bytes,
 			self.bump.as_ref()
 		]
 	}
+
+	/// Seeds of orders created before orders were per outcome
+	pub fn legacy_auth_seeds(&self) -> [&[u8]; 4] {
+		[
+			b"order".as_ref(),
+			self.event.as_ref(),
+			self.authority.as_ref(),
+			self.bump.as_ref()
+		]
+	}
+
+	/// Whether `key` is this order's address under either the current or the legacy seeds, so
+	/// orders from before the upgrade can still be settled
+	pub fn is_order_address(&self, key: &Pubkey, program_id: &Pubkey) -> bool {
+		let outcome_bytes = [self.outcome as u8];
+		let is_address = |seeds: &[&[u8]]| {
+			Pubkey::create_program_address(seeds, program_id) == Ok(*key)
+		};
+
+		is_address(&self.auth_seeds(&outcome_bytes)) || is_address(&self.legacy_auth_seeds())
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use crate::state::{Order, Outcome};
+	use solana_program::pubkey::Pubkey;
+
+	fn order_at(seeds: &[&[u8]], event: Pubkey, authority: Pubkey) -> (Order, Pubkey) {
+		let (address, bump) = Pubkey::find_program_address(seeds, &crate::ID);
+		let order = Order {
+			bump: [bump],
+			version: 0,
+			authority,
+			event,
+			outcome: Outcome::Up,
+			amount: 0,
+			referrer: Pubkey::default(),
+		};
+
+		(order, address)
+	}
+
+	#[test]
+	fn order_address() {
+		let (event, authority) = (Pubkey::new_unique(), Pubkey::new_unique());
+		let seeds: &[&[u8]] = &[b"order", event.as_ref(), authority.as_ref(), &[Outcome::Up as u8]];
+		let (order, address) = order_at(seeds, event, authority);
+		assert!(order.is_order_address(&address, &crate::ID));
+		assert!(!order.is_order_address(&Pubkey::new_unique(), &crate::ID));
+	}
+
+	#[test]
+	fn legacy_order_address() {
+		let (event, authority) = (Pubkey::new_unique(), Pubkey::new_unique());
+		let seeds: &[&[u8]] = &[b"order", event.as_ref(), authority.as_ref()];
+		let (order, address) = order_at(seeds, event, authority);
+		assert!(order.is_order_address(&address, &crate::ID));
+	}
 }
@@ -0,0 +1,17 @@
+use anchor_lang::prelude::*;
+use solana_program::pubkey::Pubkey;
+
+pub const REFERRER_SIZE: usize = 8 + 1 + 32 + 32 + 8 + 8 + 64;
+
+#[account]
+pub struct Referrer {
+	/// Bump seed used to generate the program address
+	pub bump: [u8; 1],
+	pub event_config: Pubkey,
+	/// Account referring orders and receiving the referral rewards
+	pub authority: Pubkey,
+	/// Lifetime number of referred orders that paid a reward
+	pub order_count: u64,
+	/// Lifetime rewards paid to the referrer
+	pub total_rewards: u64,
+}
@@ -8,6 +8,7 @@ use solana_program::program_pack::{IsInitialized, Pack};
 use spl_associated_token_account::instruction::create_associated_token_account;
 use spl_token::state::Account as SplAccount;
 use crate::error::Error;
+use crate::state::Outcome;
 
 #[error_code]
 pub enum UtilError {
@@ -264,14 +265,180 @@ pub fn make_ata<'a>(
     Ok(())
 }
 
+/// Converts a price with exponent `pyth_expo` to `target_decimals` decimals, scaling up or down so
+/// prices read with different exponents, like switchboard rounds of different scales, compare
+/// correctly. Decimals past `target_decimals` are rounded down.
 pub fn get_price_with_decimal_change(pyth_price: i64, pyth_expo: i32, target_decimals: u8) -> Result<u64> {
-    let pyth_feed_decimals = (pyth_expo * -1) as u8;
-    let power_change = if pyth_feed_decimals > target_decimals {
-        pyth_feed_decimals - target_decimals
+    change_price_decimals(pyth_price, pyth_expo, target_decimals)
+        .ok_or_else(|| error!(Error::InvalidPrice))
+}
+
+/// Same as `get_price_with_decimal_change` without the program error so other programs can share
+/// it. Returns `None` for negative prices or results that don't fit in a u64.
+pub fn change_price_decimals(pyth_price: i64, pyth_expo: i32, target_decimals: u8) -> Option<u64> {
+    let price = u64::try_from(pyth_price).ok()? as u128;
+    let power_change = pyth_expo as i64 + target_decimals as i64;
+    let power = 10_u128.checked_pow(power_change.unsigned_abs() as u32);
+    let price = if power_change >= 0 {
+        price.checked_mul(power?)?
+    } else {
+        // Anything past u128 precision rounds to 0
+        power.map_or(0, |power| price / power)
+    };
+
+    u64::try_from(price).ok()
+}
+
+/// Whether the confidence interval is wider than `max_conf_bps` of the price. A `max_conf_bps` of 0
+/// accepts any confidence.
+pub fn is_conf_too_wide(price: i64, conf: u64, max_conf_bps: u32) -> bool {
+    if max_conf_bps == 0 {
+        return false
+    }
+
+    (conf as u128) * 10_000 > (price.max(0) as u128) * max_conf_bps as u128
+}
+
+/// Outcome of a price move from lock to settle. Moves smaller than `band` count as no move.
+pub fn get_price_outcome(lock_price: u64, settle_price: u64, band: u64) -> Outcome {
+    let change = settle_price.abs_diff(lock_price);
+    if change == 0 || change < band {
+        Outcome::Same
+    } else if settle_price > lock_price {
+        Outcome::Up
+    } else {
+        Outcome::Down
+    }
+}
+
+/// Builds a recurring cron schedule that triggers every `interval_seconds` in line with `start`.
+/// Only intervals that evenly divide a minute, an hour or a day can be expressed.
+pub fn get_interval_schedule(start: i64, interval_seconds: u32) -> Option<String> {
+    let interval = interval_seconds as i64;
+    if interval == 0 {
+        return None;
+    }
+
+    let sec = start.rem_euclid(60);
+    let min = start.div_euclid(60).rem_euclid(60);
+    let hour = start.div_euclid(3600).rem_euclid(24);
+
+    if interval < 60 && 60 % interval == 0 {
+        Some(format!("{}/{} * * * * * *", sec % interval, interval))
+    } else if interval < 3600 && interval % 60 == 0 && 3600 % interval == 0 {
+        let step = interval / 60;
+        Some(format!("{} {}/{} * * * * *", sec, min % step, step))
+    } else if interval <= 86400 && interval % 3600 == 0 && 86400 % interval == 0 {
+        let step = interval / 3600;
+        Some(format!("{} {} {}/{} * * * *", sec, min, hour % step, step))
     } else {
-        0
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::state::Outcome;
+    use crate::util::{
+        get_interval_schedule,
+        get_price_with_decimal_change,
+        get_price_outcome,
+        is_conf_too_wide,
     };
-    let power = 10_u128.pow(power_change as u32);
-    let price = (pyth_price as u128).checked_div(power).unwrap() as u64;
-    Ok(price)
-}
\ No newline at end of file
+
+    #[test]
+    fn interval_schedule_zero() {
+        assert_eq!(None, get_interval_schedule(1_672_531_200, 0));
+    }
+
+    #[test]
+    fn interval_schedule_seconds() {
+        // 2023-01-01 00:00:10
+        let value = get_interval_schedule(1_672_531_210, 15).unwrap();
+        assert_eq!("10/15 * * * * * *", value);
+    }
+
+    #[test]
+    fn interval_schedule_minutes() {
+        // 2023-01-01 00:07:30
+        let value = get_interval_schedule(1_672_531_650, 300).unwrap();
+        assert_eq!("30 2/5 * * * * *", value);
+    }
+
+    #[test]
+    fn interval_schedule_hours() {
+        // 2023-01-01 05:00:00
+        let value = get_interval_schedule(1_672_549_200, 14_400).unwrap();
+        assert_eq!("0 0 1/4 * * * *", value);
+    }
+
+    #[test]
+    fn interval_schedule_day() {
+        // 2023-01-01 05:00:00
+        let value = get_interval_schedule(1_672_549_200, 86_400).unwrap();
+        assert_eq!("0 0 5/24 * * * *", value);
+    }
+
+    #[test]
+    fn interval_schedule_uneven() {
+        assert_eq!(None, get_interval_schedule(1_672_531_200, 90));
+        assert_eq!(None, get_interval_schedule(1_672_531_200, 420));
+        assert_eq!(None, get_interval_schedule(1_672_531_200, 172_800));
+    }
+
+    #[test]
+    fn conf_unchecked() {
+        assert!(!is_conf_too_wide(100, 100, 0));
+    }
+
+    #[test]
+    fn conf_within_max() {
+        assert!(!is_conf_too_wide(10_000, 10, 10));
+        assert!(is_conf_too_wide(10_000, 11, 10));
+        assert!(is_conf_too_wide(-1, 1, 10));
+    }
+
+    #[test]
+    fn price_outcome_no_band() {
+        assert_eq!(Outcome::Same, get_price_outcome(100, 100, 0));
+        assert_eq!(Outcome::Up, get_price_outcome(100, 101, 0));
+        assert_eq!(Outcome::Down, get_price_outcome(100, 99, 0));
+    }
+
+    #[test]
+    fn price_outcome_band() {
+        assert_eq!(Outcome::Same, get_price_outcome(100, 104, 5));
+        assert_eq!(Outcome::Same, get_price_outcome(100, 96, 5));
+        assert_eq!(Outcome::Up, get_price_outcome(100, 105, 5));
+        assert_eq!(Outcome::Down, get_price_outcome(100, 95, 5));
+    }
+
+    #[test]
+    fn decimal_change_down() {
+        assert_eq!(2_315, get_price_with_decimal_change(231_502, -4, 2).unwrap());
+        assert_eq!(231_502, get_price_with_decimal_change(231_502, -4, 4).unwrap());
+    }
+
+    #[test]
+    fn decimal_change_up() {
+        assert_eq!(231_500, get_price_with_decimal_change(2_315, -2, 4).unwrap());
+        assert_eq!(23_000, get_price_with_decimal_change(23, 0, 3).unwrap());
+        assert!(get_price_with_decimal_change(i64::MAX, -2, 4).is_err());
+    }
+
+    #[test]
+    fn decimal_change_negative_price() {
+        assert!(get_price_with_decimal_change(-1, -2, 2).is_err());
+    }
+
+    #[test]
+    fn price_outcome_different_scales() {
+        // Switchboard rounds can be reported with different scales
+        let lock_price = get_price_with_decimal_change(2_315, -2, 4).unwrap();
+        let settle_price = get_price_with_decimal_change(231_502, -4, 4).unwrap();
+        assert_eq!(Outcome::Up, get_price_outcome(lock_price, settle_price, 0));
+
+        let settle_price = get_price_with_decimal_change(231_500, -4, 4).unwrap();
+        assert_eq!(Outcome::Same, get_price_outcome(lock_price, settle_price, 0));
+    }
+}